@@ -0,0 +1,174 @@
+// Every control in the game goes through an `Action`, so they can be rebound in the settings
+// file. Systems read `Input<Action>` the same way they would read `Input<KeyCode>`.
+
+use std::collections::BTreeMap;
+
+use bevy::input::InputSystem;
+
+use crate::prelude::*;
+
+pub struct ActionsPlugin;
+
+impl Plugin for ActionsPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .insert_resource(ActionMap::load(CONTROLS_PATH))
+            .init_resource::<Input<Action>>()
+            .add_systems(PreStartup, bind_graybox)
+            .add_systems(PreUpdate, update_actions.in_set(ActionSystem).after(InputSystem));
+    }
+}
+
+pub const CONTROLS_PATH: &str = "settings/controls.ron";
+
+#[derive(SystemSet, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ActionSystem;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Action {
+    MoveLeft,
+    MoveRight,
+    Jump,
+    ToggleEditor,
+    Save,
+    TogglePhysicsDebug,
+    StopFrame,
+    ResumeFrame,
+    OpenGraybox,
+    /// Moves the editor's multiselection, and the tiles and objects in it.
+    ShiftSelectionLeft,
+    ShiftSelectionRight,
+    ShiftSelectionUp,
+    ShiftSelectionDown,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Binding {
+    /// All of these keys held at once.
+    Keys(Vec<KeyCode>),
+    Gamepad(GamepadButtonType),
+    /// A stick or trigger pushed past `threshold`. A negative threshold means pushed the
+    /// other way.
+    GamepadAxis { axis: GamepadAxisType, threshold: f32 },
+}
+
+/// Ctrl on windows, and command everywhere else.
+const CONTROL_KEY: KeyCode =
+    if cfg!(windows) { KeyCode::ControlLeft } else { KeyCode::SuperLeft };
+
+#[derive(Resource, Debug, Clone, Serialize, Deserialize)]
+pub struct ActionMap {
+    pub bindings: BTreeMap<Action, Vec<Binding>>,
+}
+
+impl Default for ActionMap {
+    fn default() -> Self {
+        use Action::*;
+        use Binding::*;
+        use KeyCode as KC;
+
+        let bindings = [
+            (MoveLeft, vec![
+                Keys(vec![KC::A]),
+                Gamepad(GamepadButtonType::DPadLeft),
+                GamepadAxis { axis: GamepadAxisType::LeftStickX, threshold: -0.5 },
+            ]),
+            (MoveRight, vec![
+                Keys(vec![KC::D]),
+                Gamepad(GamepadButtonType::DPadRight),
+                GamepadAxis { axis: GamepadAxisType::LeftStickX, threshold: 0.5 },
+            ]),
+            (Jump, vec![
+                Keys(vec![KC::Space]),
+                Keys(vec![KC::W]),
+                Gamepad(GamepadButtonType::South),
+            ]),
+            (ToggleEditor, vec![Keys(vec![KC::T]), Gamepad(GamepadButtonType::Select)]),
+            (Save, vec![Keys(vec![CONTROL_KEY, KC::S])]),
+            (TogglePhysicsDebug, vec![Keys(vec![CONTROL_KEY, KC::D])]),
+            (StopFrame, vec![Keys(vec![CONTROL_KEY, KC::F])]),
+            (ResumeFrame, vec![Keys(vec![CONTROL_KEY, KC::ShiftLeft, KC::F])]),
+            (OpenGraybox, vec![Keys(vec![CONTROL_KEY, KC::G])]),
+            (ShiftSelectionLeft, vec![Keys(vec![KC::Left])]),
+            (ShiftSelectionRight, vec![Keys(vec![KC::Right])]),
+            (ShiftSelectionUp, vec![Keys(vec![KC::Up])]),
+            (ShiftSelectionDown, vec![Keys(vec![KC::Down])]),
+        ];
+
+        Self { bindings: bindings.into_iter().collect() }
+    }
+}
+
+impl ActionMap {
+    /// Reads the action map from `path`. Actions the file doesn't mention keep their default
+    /// bindings, and if the file is missing or broken, all of them do.
+    pub fn load(path: &str) -> Self {
+        let mut map = Self::default();
+
+        let from_file = match std::fs::read_to_string(path) {
+            Ok(file) => file,
+            Err(err) => {
+                info!("using default controls, couldn't read {path}: {err}");
+                return map;
+            }
+        };
+
+        match ron::from_str::<ActionMap>(&from_file) {
+            Ok(from_file) => map.bindings.extend(from_file.bindings),
+            Err(err) => warn!("using default controls, couldn't parse {path}: {err}"),
+        }
+
+        map
+    }
+}
+
+fn binding_active(
+    binding: &Binding,
+    keys: &Input<KeyCode>,
+    gamepads: &Gamepads,
+    buttons: &Input<GamepadButton>,
+    axes: &Axis<GamepadAxis>,
+) -> bool {
+    match binding {
+        Binding::Keys(chord) => !chord.is_empty() && chord.iter().all(|key| keys.pressed(*key)),
+        Binding::Gamepad(button_type) => gamepads.iter()
+            .any(|gamepad| buttons.pressed(GamepadButton::new(gamepad, *button_type))),
+        Binding::GamepadAxis { axis, threshold } => gamepads.iter().any(|gamepad| {
+            let value = axes.get(GamepadAxis::new(gamepad, *axis)).unwrap_or(0.);
+            if *threshold < 0. { value <= *threshold } else { value >= *threshold }
+        }),
+    }
+}
+
+fn update_actions(
+    map: Res<ActionMap>,
+    keys: Res<Input<KeyCode>>,
+    gamepads: Res<Gamepads>,
+    buttons: Res<Input<GamepadButton>>,
+    axes: Res<Axis<GamepadAxis>>,
+    mut actions: ResMut<Input<Action>>,
+) {
+    actions.clear();
+
+    for (action, bindings) in &map.bindings {
+        let active = bindings.iter()
+            .any(|binding| binding_active(binding, &keys, &gamepads, &buttons, &axes));
+
+        if active && !actions.pressed(*action) {
+            actions.press(*action);
+        } else if !active && actions.pressed(*action) {
+            actions.release(*action);
+        }
+    }
+}
+
+/// Graybox takes a plain list of keys to open it, so it gets the first keyboard binding.
+fn bind_graybox(map: Res<ActionMap>, mut graybox: ResMut<GrayboxSettings>) {
+    let chord = map.bindings.get(&Action::OpenGraybox).into_iter().flatten()
+        .find_map(|binding| match binding {
+            Binding::Keys(chord) => Some(chord.clone()),
+            _ => None,
+        });
+
+    graybox.open_graybox_command = chord.unwrap_or_default();
+}
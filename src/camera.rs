@@ -1,12 +1,15 @@
 use crate::{prelude::*, world::{LoadedLevel, helpers::level_is_in_position}};
+use crate::world::objects::{CameraZone, CameraFollow};
 
 pub struct CameraPlugin;
 
 impl Plugin for CameraPlugin {
     fn build(&self, app: &mut App) {
         app
+            .add_event::<CameraShake>()
             .add_systems(Startup, setup_camera)
-            .add_systems(Update, camera_update);
+            .add_systems(Update, (add_camera_shake, camera_update).chain())
+            .enable_inspection::<CameraController>();
     }
 }
 
@@ -14,6 +17,12 @@ impl Plugin for CameraPlugin {
 pub struct GameCamera {
     pub bounds: Rect,
     pub move_mode: CameraMoveMode,
+    /// Where the camera is, before shaking and snapping to the pixel grid.
+    position: Vec2,
+    /// The point the dead zone is centered on.
+    focus: Vec2,
+    look_ahead: f32,
+    trauma: f32,
 }
 
 #[derive(Default, PartialEq, Eq)]
@@ -23,50 +32,185 @@ pub enum CameraMoveMode {
     Free,
 }
 
+/// Tuning for how the camera follows the player. Lives on the camera, so it can be tweaked
+/// in graybox.
+#[derive(Component, Reflect)]
+pub struct CameraController {
+    pub follow_speed: f32,
+    /// Slower than `follow_speed`, so the camera doesn't bob up and down with every jump.
+    pub vertical_speed: f32,
+    /// How far ahead of the player the camera looks, in the direction they're moving.
+    pub look_ahead: f32,
+    pub look_ahead_speed: f32,
+    /// Half the size of the box the player can move around in without moving the camera.
+    pub dead_zone: Vec2,
+    /// How far the camera moves at full trauma.
+    pub max_shake: f32,
+    /// How much trauma wears off per second.
+    pub shake_decay: f32,
+    pub pixel_snap: bool,
+}
+
+impl Default for CameraController {
+    fn default() -> Self {
+        Self {
+            follow_speed: 0.1,
+            vertical_speed: 0.04,
+            look_ahead: 24.,
+            look_ahead_speed: 0.03,
+            dead_zone: Vec2::new(8., 32.),
+            max_shake: 6.,
+            shake_decay: 1.5,
+            pixel_snap: true,
+        }
+    }
+}
+
+/// Shakes the camera. Trauma adds up to a maximum of 1, and the shake gets stronger with the
+/// square of it.
+#[derive(Event)]
+pub struct CameraShake {
+    pub trauma: f32,
+}
+
+const CAMERA_SCALE: f32 = 0.25;
+
 fn setup_camera(mut commands: Commands) {
     commands.spawn((
         Camera2dBundle {
             projection: OrthographicProjection {
                 far: 1000.,
                 near: -1000.,
-                scale: 0.25,
+                scale: CAMERA_SCALE,
                 ..default()
             },
             ..default()
         },
         GameCamera::default(),
+        CameraController::default(),
         Name::new("Camera"),
     ));
 }
 
+fn add_camera_shake(mut shakes: EventReader<CameraShake>, mut camera: Query<&mut GameCamera>) {
+    let mut camera = camera.single_mut();
+
+    for shake in shakes.read() {
+        camera.trauma = (camera.trauma + shake.trauma).clamp(0., 1.);
+    }
+}
+
+/// Moves `target` so that a view of `area` around it stays inside `bounds`. If the bounds are
+/// smaller than the view along an axis, the view is centered on them along that axis.
+fn clamp_to_bounds(target: Vec2, bounds: Rect, area: Rect) -> Vec2 {
+    let min = bounds.min - area.min;
+    let max = bounds.max - area.max;
+
+    Vec2::new(
+        if min.x <= max.x { target.x.clamp(min.x, max.x) } else { bounds.center().x },
+        if min.y <= max.y { target.y.clamp(min.y, max.y) } else { bounds.center().y },
+    )
+}
+
+/// Moves `focus` just enough along one axis for `player` to be within `dead_zone` of it.
+fn follow_with_dead_zone(focus: f32, player: f32, dead_zone: f32) -> f32 {
+    focus.clamp(player - dead_zone, player + dead_zone)
+}
+
 fn camera_update(
-    mut camera: Query<(&mut Transform, &OrthographicProjection, &mut GameCamera)>,
-    player: Query<&Transform, (With<Player>, Without<OrthographicProjection>)>,
+    mut camera: Query<(
+        &mut Transform,
+        &mut OrthographicProjection,
+        &mut GameCamera,
+        &CameraController,
+    )>,
+    player: Query<(&Transform, &Velocity, Entity),
+        (With<Player>, Without<OrthographicProjection>)>,
+    casts: PlayerCasts,
     levels: Query<(&LoadedLevel, &Transform), Without<GameCamera>>,
+    zones: Query<(&CameraZone, &GlobalTransform)>,
+    time: Res<Time>,
 ) {
-    const CAMERA_SPEED: f32 = 0.1;
-    let player = player.single();
-    let mut camera = camera.single_mut();
+    let (player, velocity, player_entity) = player.single();
+    let (mut transform, mut projection, mut camera, controller) = camera.single_mut();
+    let player_pos = player.translation.xy();
 
     for level in &levels {
-        if let Some(bounds) = level_is_in_position(level, player.translation.xy()) {
-            camera.2.bounds = bounds;
+        if let Some(bounds) = level_is_in_position(level, player_pos) {
+            camera.bounds = bounds;
         }
     }
 
-    let target = match camera.2.move_mode {
+    // if zones overlap, the smallest one wins.
+    let zone = zones.iter()
+        .map(|zone| (zone.0, CameraZone::rect(zone.1)))
+        .filter(|zone| zone.1.contains(player_pos))
+        .min_by(|a, b| (a.1.size().x * a.1.size().y)
+            .total_cmp(&(b.1.size().x * b.1.size().y)));
+
+    let (bounds, follow, zoom) = match zone {
+        Some((zone, rect)) => (rect, zone.follow, zone.zoom),
+        None => (camera.bounds, CameraFollow::Free, 1.),
+    };
+
+    match camera.move_mode {
         CameraMoveMode::SnapToBounds => {
-            let mut target = player.translation.xy();
-            target.x = target.x.max(camera.2.bounds.min.x - camera.1.area.min.x);
-            target.x = target.x.min(camera.2.bounds.max.x - camera.1.area.max.x);
-            target.y = target.y.max(camera.2.bounds.min.y - camera.1.area.min.y);
-            target.y = target.y.min(camera.2.bounds.max.y - camera.1.area.max.y);
-            target
+            // both the position and the zoom ease towards the new zone, so crossing from one
+            // zone into another blends between them.
+            projection.scale = lerp(
+                projection.scale, CAMERA_SCALE / zoom.max(0.1), controller.follow_speed);
+
+            let look_ahead_goal = if velocity.linvel.x.abs() > 1. {
+                velocity.linvel.x.signum() * controller.look_ahead
+            } else {
+                camera.look_ahead
+            };
+            camera.look_ahead =
+                lerp(camera.look_ahead, look_ahead_goal, controller.look_ahead_speed);
+
+            camera.focus.x =
+                follow_with_dead_zone(camera.focus.x, player_pos.x, controller.dead_zone.x);
+            // hops inside the dead zone are ignored, but once the player has landed
+            // somewhere, the camera settles on them. Standing still in the air at the top
+            // of a jump doesn't count as landing.
+            camera.focus.y = if casts.is_grounded(player_entity) {
+                player_pos.y
+            } else {
+                follow_with_dead_zone(camera.focus.y, player_pos.y, controller.dead_zone.y)
+            };
+
+            let target = match follow {
+                CameraFollow::Locked => bounds.center(),
+                CameraFollow::HorizontalOnly =>
+                    Vec2::new(camera.focus.x + camera.look_ahead, bounds.center().y),
+                CameraFollow::Free => camera.focus + Vec2::X * camera.look_ahead,
+            };
+            let target = clamp_to_bounds(target, bounds, projection.area);
+
+            camera.position.x = lerp(camera.position.x, target.x, controller.follow_speed);
+            camera.position.y = lerp(camera.position.y, target.y, controller.vertical_speed);
         },
         CameraMoveMode::Free => {
-            player.translation.xy()
+            camera.focus = player_pos;
+            camera.position = camera.position.lerp(player_pos, controller.follow_speed);
         }
-    };
+    }
+
+    camera.trauma = (camera.trauma - controller.shake_decay * time.delta_seconds()).max(0.);
+
+    // a few out-of-phase sines instead of random numbers, so that recorded input plays back
+    // the same way.
+    let t = time.elapsed_seconds();
+    let shake = Vec2::new(
+        (t * 47.).sin() + (t * 23.).sin() * 0.5,
+        (t * 53. + 1.3).sin() + (t * 29. + 0.7).sin() * 0.5,
+    ) / 1.5 * controller.max_shake * camera.trauma * camera.trauma;
+
+    let mut translation = camera.position + shake;
+    if controller.pixel_snap {
+        // one screen pixel is `scale` world units wide
+        translation = (translation / projection.scale).round() * projection.scale;
+    }
 
-    camera.0.translation = camera.0.translation.lerp(target.extend(0.), CAMERA_SPEED);
+    transform.translation = translation.extend(0.);
 }
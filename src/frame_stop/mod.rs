@@ -2,14 +2,14 @@ use std::time::Duration;
 
 use bevy::input::InputSystem;
 
-use crate::prelude::*;
+use crate::{prelude::*, actions::ActionSystem};
 
 pub struct FrameStopPlugin;
 
 impl Plugin for FrameStopPlugin {
     fn build(&self, app: &mut App) {
         app
-            .add_systems(PreUpdate, frame_stop.after(InputSystem))
+            .add_systems(PreUpdate, frame_stop.after(InputSystem).after(ActionSystem))
             .init_resource::<FrameStopState>();
     }
 }
@@ -18,14 +18,12 @@ impl Plugin for FrameStopPlugin {
 enum FrameStopState { Stopped, #[default] Continue }
 
 fn frame_stop(
-    keys: Res<Input<KeyCode>>, 
+    actions: Res<Input<Action>>,
     mut time: ResMut<Time>,
     mut timev: ResMut<Time<Virtual>>,
     mut timef: ResMut<Time<Fixed>>,
     mut stop_state: ResMut<FrameStopState>
 ) {
-    let control_pressed = keys.pressed(crate::CONTROL_KEY);
-
     if *stop_state == FrameStopState::Stopped {
         let pre_time = *time;
         let pre_timev = *timev;
@@ -35,11 +33,11 @@ fn frame_stop(
         *timev = pre_timev;
         *timef = pre_timef;
 
-        if control_pressed && keys.pressed(KeyCode::ShiftLeft) && keys.pressed(KeyCode::F) {
+        if actions.pressed(Action::ResumeFrame) {
             *stop_state = FrameStopState::Continue; 
         }
     } else {
-        if control_pressed && keys.just_pressed(KeyCode::F) {
+        if actions.just_pressed(Action::StopFrame) {
             *stop_state = FrameStopState::Stopped; 
         }
     }
@@ -1,4 +1,4 @@
-use crate::prelude::*;
+use crate::{prelude::*, word::movement_config::*};
 
 pub struct AssetPlugin;
 
@@ -19,6 +19,7 @@ pub struct MiscAssets {
     pub tileset: Handle<Image>,
     pub font: Handle<Font>,
     pub word_tag_sprites: HashMap<WordID, Handle<Image>>,
+    pub movement: Handle<MovementConfig>,
 }
 
 fn load_assets(
@@ -31,6 +32,7 @@ fn load_assets(
     assets.square_pink = asset_server.load("square_pink.bmp");
     assets.horse = asset_server.load("horse.bmp");
     assets.tileset = asset_server.load("tileset.bmp");
+    assets.movement = asset_server.load(MOVEMENT_CONFIG_PATH);
 
     let font = asset_server.load("fonts/tempfont.ttf");
     assets.font = font.clone();
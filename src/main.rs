@@ -20,9 +20,7 @@ pub(crate) mod prelude {
     pub use super::load_assets::MiscAssets;
     pub use super::word::*;
     pub use super::camera::*;
-
-    pub const CONTROL_KEY: KeyCode = 
-        if cfg!(windows) { KeyCode::ControlLeft } else { KeyCode::SuperLeft };
+    pub use super::actions::Action;
 
     pub fn lerp(a: f32, b: f32, n: f32) -> f32 {
         debug_assert!(n >= 0. && n <= 1.);
@@ -40,10 +38,6 @@ pub(crate) mod prelude {
     }
 
     pub fn empty_grid<T: Default>() -> Grid<T> { Grid::new(0, 0) }
-
-    pub fn grid_size<T>(grid: &Grid<T>) -> Vec2 {
-        Vec2::new(grid.rows() as f32, grid.cols() as f32)
-    }
 }
 
 use bevy::window::CompositeAlphaMode;
@@ -55,6 +49,7 @@ mod load_assets;
 mod helpers;
 mod frame_stop;
 mod camera;
+mod actions;
 
 use word::*;
 use load_assets::*;
@@ -86,10 +81,12 @@ fn main() {
             load_assets::AssetPlugin,
             world::WorldPlugin,
             camera::CameraPlugin,
-            RapierPhysicsPlugin::<NoUserData>::default(),
+            actions::ActionsPlugin,
+            RapierPhysicsPlugin::<world::tiles::OneWayPlatformHooks>::default(),
             RapierDebugRenderPlugin { enabled: false, ..default() },
             GrayboxPlugin {
-                open_graybox_command: vec![CONTROL_KEY, KeyCode::G],
+                // bound from the action map on startup
+                open_graybox_command: vec![],
             },
             FrameStopPlugin,
         ))
@@ -106,10 +103,10 @@ fn main() {
 
 
 fn optional_debug_physics_view(
-    keyboard: Res<Input<KeyCode>>,
+    actions: Res<Input<Action>>,
     mut physics_debug_context: ResMut<DebugRenderContext>,
 ) {
-    if keyboard.pressed(CONTROL_KEY) && keyboard.just_pressed(KeyCode::D) {
+    if actions.just_pressed(Action::TogglePhysicsDebug) {
         physics_debug_context.enabled = !physics_debug_context.enabled;
     }
 }
@@ -1,6 +1,6 @@
 use crate::prelude::*;
 
-use super::spawn::*;
+use super::{spawn::*, movement_config::MovementTuning};
 
 #[derive(WorldQuery)]
 pub struct QWordObject {
@@ -9,24 +9,19 @@ pub struct QWordObject {
 }
 
 pub fn apply_scalers(
-    mut word_objects: Query<(QWordObject, &mut Collider, &GlobalTransform, &mut Transform)>,
+    mut word_objects: Query<(
+        QWordObject,
+        &mut Collider,
+        &GlobalTransform,
+        &mut Transform,
+        Option<&MovementTuning>,
+    )>,
     transforms: Query<&GlobalTransform>,
     phys_context: Res<RapierContext>,
 ){
-    const SHRINK_SPEED: f32 = 0.15;
-
     for mut object in &mut word_objects {
-        let target_scale = {
-            let mut target_scale = Vec2::ONE;
-
-            let adjectives = &object.0.words.adjectives;
-
-            if adjectives.tall { target_scale.y *= 4.; }
-            if adjectives.wide { target_scale.x *= 4.; }
-            if adjectives.baby { target_scale *= 0.5; }
-
-            target_scale
-        };
+        let shrink_speed = object.4.copied().unwrap_or_default().shrink_speed;
+        let target_scale = object.0.words.adjectives.target_scale();
 
         let old_scale = object.3.scale.xy();
         if (old_scale - target_scale).length() <= 0.01 {
@@ -34,7 +29,7 @@ pub fn apply_scalers(
         }
 
 
-        let scale_diff = Vec2::splat(1.) + (target_scale - old_scale) * SHRINK_SPEED;
+        let scale_diff = Vec2::splat(1.) + (target_scale - old_scale) * shrink_speed;
 
         let babied_shape = object.1.as_typed_shape()
             .raw_scale_by(scale_diff, 0)
@@ -70,21 +65,22 @@ pub fn apply_scalers(
 }
 
 pub fn apply_fluttering(
-    mut flutters: Query<QWordObject>,
+    mut flutters: Query<(QWordObject, Option<&MovementTuning>)>,
     parents: Query<&Parent>,
     mut velocities: Query<&mut Velocity>,
     time: Res<Time>,
 ) {
-    for flutter in &mut flutters {
+    for (flutter, tuning) in &mut flutters {
         let Some(direction) = flutter.words.adjectives.fluttering else { continue };
+        let tuning = tuning.copied().unwrap_or_default();
 
         for ancestor in parents.iter_ancestors(flutter.entity) {
             if let Ok(mut velocity) = velocities.get_mut(ancestor) {
                 let dir_vector = match direction {
-                    FlutteringDirection::Up => Vec2::new(0., 4.),
+                    FlutteringDirection::Up => Vec2::new(0., tuning.flutter_up),
                     FlutteringDirection::Down => todo!(),
                     FlutteringDirection::Left => todo!(),
-                    FlutteringDirection::Right => Vec2::new(16., 0.),
+                    FlutteringDirection::Right => Vec2::new(tuning.flutter_right, 0.),
                 };
 
                 if (velocity.linvel * dir_vector).length() < dir_vector.length() * 100. {
@@ -5,13 +5,16 @@ use crate::prelude::*;
 
 pub mod ui;
 pub mod movement;
+pub mod movement_config;
 pub mod spawn;
 pub mod apply_words;
 pub mod word_id;
+pub mod sentence;
 
 use bevy::{utils::HashSet, ecs::schedule::ScheduleLabel};
 pub use movement::*;
 pub use word_id::*;
+pub use sentence::*;
 
 use self::{ui::*, spawn::SentenceSpawn};
 
@@ -33,9 +36,11 @@ impl Plugin for PlayerPlugin {
             .add_event::<SentenceStructureChanged>()
             .add_event::<SentenceSpawn>()
             .add_event::<VocabChange>()
+            .add_event::<RestoreSentence>()
             .add_systems(Update, (
                 // sentence ui / word remake routine
                 (
+                    ui::restore_sentences,
                     ui::regenerate_sentence_structure,
                     ( 
                         ui::do_snap,
@@ -46,20 +51,27 @@ impl Plugin for PlayerPlugin {
                     spawn::disable_physics_for_invalid_sentence_structures,
                 ).in_set(SentenceModificationRoutine).chain(),
                 ui::update_vocabulary,
+                ui::indicate_sentence_locks.after(SentenceModificationRoutine),
+                (ui::spawn_floating_sentence_bars, ui::place_floating_sentence_bars).chain(),
             ))
             .add_systems(
                 // these run deffered, after the node spawn commands issued by
                 // update_sentence_ui.
                 PostSentenceModificationActionsSet, 
-                (ui::indicate_sentence_section_locks, ui::reorder_sentence_ui),
+                ui::reorder_sentence_ui,
             )
             .add_systems(FixedUpdate, (
                 apply_words::apply_scalers,
                 apply_words::apply_fluttering,
             ).after(SentenceModificationRoutine))
             .add_systems(Update, (
+                movement_config::apply_movement_config,
                 movement::do_movement,
-            ));
+                movement::do_jump,
+            ).chain())
+            .init_asset::<movement_config::MovementConfig>()
+            .register_asset_loader(movement_config::MovementConfigLoader)
+            .enable_inspection::<movement_config::MovementTuning>();
     }
 }
 
@@ -80,6 +92,8 @@ pub struct PhraseData {
     pub word: Option<WordID>,
     pub kind: PhraseKind,
     pub locked: bool,
+    /// Added by the world instead of the player, like "fluttering" from a fan.
+    pub injected: bool,
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
@@ -99,7 +113,7 @@ pub type PhraseMap = SlotMap<PhraseID, PhraseData>;
 
 /// Components that act as the parent of a word collection. For example, the player has a
 /// SentenceStructure.
-#[derive(Debug, Component)]
+#[derive(Debug, Clone, Component)]
 pub struct SentenceStructure {
     pub sentence: PhraseMap,
     pub root: PhraseID,
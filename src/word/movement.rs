@@ -1,6 +1,6 @@
-use crate::prelude::*;
+use crate::{prelude::*, world::{WorldCollider, objects::{MovingPlatform, Crate}}};
 
-use super::{PhraseData, PhraseKind, PhraseID, SentenceStructure, Vocabulary, WordID, ui::VocabChange, spawn::*, apply_words::QWordObject};
+use super::{movement_config::MovementTuning, SentenceStructure, Vocabulary, WordID, ui::VocabChange, spawn::*, apply_words::QWordObject};
 
 #[derive(Component, Default)]
 pub struct Player;
@@ -19,19 +19,9 @@ pub fn spawn_player(
         ExternalForce::default(),
         ExternalImpulse::default(),
         LockedAxes::ROTATION_LOCKED,
-        {
-            let mut map = SlotMap::<PhraseID, PhraseData>::with_key();
-            let adjective = map.insert(
-                PhraseData { kind: PhraseKind::Adjective, ..default() });
-            let root = map.insert(
-                PhraseData { kind: PhraseKind::Noun { adjective }, ..default() });
-            SentenceStructure {
-                sentence: map,
-                root,
-                valid: false,
-            }
-        },
+        SentenceStructure::new(),
         Vocabulary::default(),
+        JumpState::default(),
         Name::new("Player"),
     )).id();
 
@@ -59,75 +49,268 @@ pub struct PlayerQuery {
     entity: Entity,
 }
 
+/// Shape casts from the player's colliders, which hit everything except the player itself
+/// and sensors. One-way platforms only get hit by downwards casts that don't start inside
+/// them.
+#[derive(SystemParam)]
+pub struct PlayerCasts<'w, 's> {
+    colliders: Query<'w, 's, (&'static GlobalTransform, &'static Collider)>,
+    children: Query<'w, 's, &'static Children>,
+    parents: Query<'w, 's, &'static Parent>,
+    sensors: Query<'w, 's, (), With<Sensor>>,
+    world_colliders: Query<'w, 's, &'static WorldCollider>,
+    phys_context: Res<'w, RapierContext>,
+}
+
+/// Gap kept between the player and whatever they're moving towards.
+const SKIN: f32 = 0.1;
+
+impl<'w, 's> PlayerCasts<'w, 's> {
+    /// Casts all of `player`'s colliders from `offset` away from where they are now, and
+    /// returns the closest hit. The casts are a little thinner than the colliders across
+    /// `direction`, so surfaces the player is resting on or pressed against don't count.
+    pub fn cast(&self, player: Entity, offset: Vec2, direction: Vec2, distance: f32)
+      -> Option<Toi> {
+        self.cast_entity(player, offset, direction, distance).map(|(_, toi)| toi)
+    }
+
+    /// Like `cast`, but also returns the entity that was hit.
+    pub fn cast_entity(&self, player: Entity, offset: Vec2, direction: Vec2, distance: f32)
+      -> Option<(Entity, Toi)> {
+        let thinning = if direction.x.abs() > direction.y.abs() {
+            Vec2::new(1., 0.95)
+        } else {
+            Vec2::new(0.95, 1.)
+        };
+
+        let hits_platforms = direction.y < 0.;
+        let is_platform = |entity: Entity| {
+            self.world_colliders.get(entity).is_ok_and(|c| *c == WorldCollider::OneWay)
+        };
+
+        self.children.iter_descendants(player)
+            .filter_map(|child| self.colliders.get(child).ok())
+            .filter_map(|(transform, collider)| {
+                let (_, rotation, translation) = transform.to_scale_rotation_translation();
+                let thinned = collider.as_typed_shape().raw_scale_by(thinning, 2).unwrap();
+                let thinned = Collider::from(thinned);
+                let start = translation.xy() + offset;
+
+                // casts stop right away at anything they start inside of, so a platform the
+                // player is jumping up through would count as ground until they're out of
+                // it. platforms the collider is already partway into, by more than resting
+                // on one pushes it in, are passed through instead.
+                let mut inside = Vec::new();
+                if hits_platforms {
+                    self.phys_context.intersections_with_shape(
+                        start + Vec2::Y * SKIN,
+                        rotation.z,
+                        &thinned,
+                        QueryFilter { predicate: Some(&is_platform), ..default() },
+                        |platform| { inside.push(platform); true },
+                    );
+                }
+
+                let predicate = |entity: Entity| {
+                    !self.sensors.contains(entity) &&
+                    (!is_platform(entity) || (hits_platforms && !inside.contains(&entity))) &&
+                    !self.parents.iter_ancestors(entity).any(|ancestor| ancestor == player)
+                };
+
+                self.phys_context.cast_shape(
+                    start,
+                    rotation.z,
+                    direction,
+                    &thinned,
+                    distance,
+                    QueryFilter { predicate: Some(&predicate), ..default() },
+                )
+            })
+            .min_by(|a, b| a.1.toi.total_cmp(&b.1.toi))
+    }
+
+    pub fn is_grounded(&self, player: Entity) -> bool {
+        self.ground(player).is_some()
+    }
+
+    /// What the player is standing on.
+    pub fn ground(&self, player: Entity) -> Option<Entity> {
+        self.cast_entity(player, Vec2::ZERO, Vec2::NEG_Y, GROUND_PROBE_DISTANCE)
+            .map(|(ground, _)| ground)
+    }
+
+    /// If the player is walking into a ledge no higher than `step_height`, returns how far
+    /// they have to move up to get on top of it.
+    fn step_up(&self, player: Entity, direction: Vec2, distance: f32, step_height: f32)
+      -> Option<f32> {
+        let headroom = self.cast(player, Vec2::ZERO, Vec2::Y, step_height)
+            .map_or(step_height, |hit| hit.toi - SKIN);
+        if headroom <= SKIN { return None }
+
+        let raised = Vec2::Y * headroom;
+        if self.cast(player, raised, direction, distance).is_some() { return None }
+
+        let ledge = self.cast(player, raised + direction * distance, Vec2::NEG_Y, headroom)?;
+        let rise = headroom - ledge.toi;
+        (rise > 0.).then_some(rise + SKIN)
+    }
+}
+
 pub fn do_movement(
-    input: Res<Input<KeyCode>>,
+    actions: Res<Input<Action>>,
     mut player: Query<PlayerQuery>,
-    colliders: Query<(&GlobalTransform, &Collider)>,
     children: Query<&Children>,
-    sensors: Query<&Sensor>,
+    casts: PlayerCasts,
     time: Res<Time>,
-    phys_context: Res<RapierContext>,
-    word_objects: Query<QWordObject>,
+    word_objects: Query<(QWordObject, Option<&MovementTuning>)>,
+    platforms: Query<&Velocity, (With<MovingPlatform>, Without<Player>)>,
+    crates: Query<(), With<Crate>>,
+    parents: Query<&Parent>,
 ) {
-    const MAX_X_SPEED: f32 = 32000.0;
-    const MOVE_X_ACC: f32 = 0.1;
     let mut player = player.single_mut();
-    let mut fast_multiplier = 1.;
 
     if !player.word_object.valid { return }
 
-    for child in children.iter_descendants(player.entity) {
-        if let Ok(word_object) = word_objects.get(child) && 
-          word_object.words.adjectives.fast {
-            fast_multiplier = 2.;
-        } else {
-            fast_multiplier = 1.;
-        }
+    // on a moving platform, the player moves relative to it, so standing still rides along.
+    let carried = casts.ground(player.entity)
+        .and_then(|ground| platforms.get(ground).ok())
+        .map_or(Vec2::ZERO, |platform| platform.linvel);
+
+    // keeps the player on platforms that move down, instead of falling after them
+    if carried.y < 0. && player.velocity.linvel.y <= 0. {
+        player.velocity.linvel.y = player.velocity.linvel.y.min(carried.y);
     }
 
-    let max_speed = MAX_X_SPEED / player.mass.mass * fast_multiplier;
-    let goal_speed = if input.pressed(KeyCode::D) {
+    let Some((word_object, tuning)) = children.iter_descendants(player.entity)
+        .find_map(|child| word_objects.get(child).ok()) else { return };
+    let tuning = tuning.copied().unwrap_or_default();
+
+    let fast_multiplier = if word_object.words.adjectives.fast {
+        tuning.fast_multiplier
+    } else {
+        1.
+    };
+
+    let max_speed = tuning.max_x_speed / player.mass.mass * fast_multiplier;
+    let goal_speed = if actions.pressed(Action::MoveRight) {
         max_speed
-    } else if input.pressed(KeyCode::A) {
+    } else if actions.pressed(Action::MoveLeft) {
         -max_speed
     } else {
         0.
     };
-    
-    let colliders: Vec::<(Entity, (&GlobalTransform, &Collider))> =
-        children.iter_descendants(player.entity).filter_map(|collider_entity| {
-            Some((collider_entity, colliders.get(collider_entity).ok()?))
-        }).collect();
-
-    let newvel = lerp(player.velocity.linvel.x, goal_speed, MOVE_X_ACC);
-
-    let mut is_colliding = false;
-
-    for (_, collider) in &colliders {
-        let (scale, rotation, translation) = collider.0.to_scale_rotation_translation();
-
-        let shrunk_collider = 
-            collider.1.as_typed_shape().raw_scale_by(Vec2::splat(0.99), 2).unwrap();
-
-        let intersection = phys_context.intersection_with_shape(
-            translation.xy() + Vec2::new(goal_speed, 0.) * scale.x * time.delta_seconds(),
-            rotation.z,
-            &Collider::from(shrunk_collider),
-            QueryFilter {
-                predicate: Some(&|entity| 
-                    colliders.iter().any(|(c, _)| *c != entity) &&
-                    sensors.get(entity).is_err()
-                ),
-                ..default()
-            },
-        );
-
-        if intersection.is_some() {
-            is_colliding = true;
-        }
+
+    let relative_x = player.velocity.linvel.x - carried.x;
+    let newvel = lerp(relative_x, goal_speed, tuning.move_x_acc.clamp(0., 1.)) + carried.x;
+
+    let distance = newvel.abs() * time.delta_seconds() + SKIN;
+    if newvel.abs() < 0.01 || time.delta_seconds() == 0. {
+        player.velocity.linvel.x = newvel;
+        return;
+    }
+
+    let direction = Vec2::X * newvel.signum();
+    let Some((hit_entity, hit)) = casts.cast_entity(player.entity, Vec2::ZERO, direction, distance)
+    else {
+        player.velocity.linvel.x = newvel;
+        return;
+    };
+
+    // crates get pushed, so the player keeps going and lets the physics move them
+    if parents.iter_ancestors(hit_entity).any(|ancestor| crates.contains(ancestor)) {
+        player.velocity.linvel.x = newvel;
+        return;
     }
 
-    if !is_colliding {
+    // the normal of the surface that was hit. world colliders aren't rotated, so their local
+    // space normal is the same as the world space one.
+    let normal = hit.normal1;
+    let max_slope_cos = tuning.max_slope.to_radians().cos();
+
+    if normal.y >= max_slope_cos {
+        // walkable slope: keep the horizontal speed, and move up along the surface.
+        let along_slope = Vec2::new(normal.y, -normal.x) * direction.x;
         player.velocity.linvel.x = newvel;
+        player.velocity.linvel.y = player.velocity.linvel.y
+            .max(newvel.abs() * along_slope.y / along_slope.x.abs().max(0.01));
+    } else if casts.is_grounded(player.entity) &&
+      let Some(rise) = casts.step_up(player.entity, direction, distance, tuning.step_height) {
+        player.transform.translation.y += rise;
+        player.velocity.linvel.x = newvel;
+    } else {
+        // wall: go right up to it, and let the vertical speed carry on, so the player slides
+        // along it instead of sticking.
+        player.velocity.linvel.x =
+            direction.x * (hit.toi - SKIN).max(0.) / time.delta_seconds();
+    }
+}
+
+/// Timers for the forgiving parts of jumping: coyote time lets the player jump a little
+/// after walking off a ledge, and the jump buffer remembers a jump pressed a little before
+/// landing.
+#[derive(Component, Debug)]
+pub struct JumpState {
+    since_grounded: f32,
+    since_jump_pressed: f32,
+    /// Set while rising from a jump, until the jump is cut short or starts falling.
+    rising: bool,
+}
+
+impl Default for JumpState {
+    fn default() -> Self {
+        Self { since_grounded: f32::INFINITY, since_jump_pressed: f32::INFINITY, rising: false }
+    }
+}
+
+/// How far below the player's colliders to look for ground.
+const GROUND_PROBE_DISTANCE: f32 = 1.;
+
+pub fn do_jump(
+    actions: Res<Input<Action>>,
+    mut player: Query<(PlayerQuery, &mut JumpState)>,
+    children: Query<&Children>,
+    casts: PlayerCasts,
+    time: Res<Time>,
+    word_objects: Query<(QWordObject, Option<&MovementTuning>)>,
+) {
+    let (mut player, mut jump) = player.single_mut();
+
+    if !player.word_object.valid { return }
+
+    let Some((word_object, tuning)) = children.iter_descendants(player.entity)
+        .find_map(|child| word_objects.get(child).ok()) else { return };
+    let tuning = tuning.copied().unwrap_or_default();
+
+    // while still moving up from a jump, the ground we jumped off doesn't count.
+    if player.velocity.linvel.y <= 0. {
+        jump.rising = false;
+    }
+    if !jump.rising && casts.is_grounded(player.entity) {
+        jump.since_grounded = 0.;
+    } else {
+        jump.since_grounded += time.delta_seconds();
+    }
+
+    if actions.just_pressed(Action::Jump) {
+        jump.since_jump_pressed = 0.;
+    } else {
+        jump.since_jump_pressed += time.delta_seconds();
+    }
+
+    if jump.since_jump_pressed <= tuning.jump_buffer &&
+      jump.since_grounded <= tuning.coyote_time {
+        let adjectives = &word_object.words.adjectives;
+        let mut jump_speed = tuning.jump_speed;
+        if adjectives.baby { jump_speed *= tuning.baby_jump_multiplier; }
+        if adjectives.tall { jump_speed *= tuning.tall_jump_multiplier; }
+
+        player.velocity.linvel.y = jump_speed;
+        jump.since_grounded = f32::INFINITY;
+        jump.since_jump_pressed = f32::INFINITY;
+        jump.rising = true;
+    } else if jump.rising && !actions.pressed(Action::Jump) {
+        // letting go of jump early makes for a shorter jump
+        player.velocity.linvel.y *= tuning.jump_cut;
+        jump.rising = false;
     }
 }
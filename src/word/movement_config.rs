@@ -0,0 +1,164 @@
+// Movement numbers live in `assets/default.movement.ron` instead of in the systems that use
+// them, so they can be tuned while the game is running. Each noun gets its own copy of the
+// tuning as a component, which can also be edited in graybox.
+
+use bevy::{asset::{AssetLoader, LoadContext, io::Reader, AsyncReadExt}, utils::BoxedFuture};
+
+use crate::prelude::*;
+
+use super::spawn::WordObject;
+
+pub const MOVEMENT_CONFIG_PATH: &str = "default.movement.ron";
+
+#[derive(Component, Reflect, Copy, Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MovementTuning {
+    pub max_x_speed: f32,
+    /// How much of the way to the goal speed the player gets each frame.
+    pub move_x_acc: f32,
+    pub fast_multiplier: f32,
+    /// How much of the way to its target scale a word object grows or shrinks each tick.
+    pub shrink_speed: f32,
+    pub flutter_up: f32,
+    pub flutter_right: f32,
+    pub jump_speed: f32,
+    pub baby_jump_multiplier: f32,
+    pub tall_jump_multiplier: f32,
+    /// What the upwards speed is multiplied by when jump is let go of early.
+    pub jump_cut: f32,
+    /// How long after leaving the ground the player can still jump, in seconds.
+    pub coyote_time: f32,
+    /// How long before landing a jump press is remembered, in seconds.
+    pub jump_buffer: f32,
+    /// The highest ledge the player walks up onto without jumping.
+    pub step_height: f32,
+    /// The steepest slope the player can walk up, in degrees.
+    pub max_slope: f32,
+}
+
+impl Default for MovementTuning {
+    fn default() -> Self {
+        Self {
+            max_x_speed: 32000.,
+            move_x_acc: 0.1,
+            fast_multiplier: 2.,
+            shrink_speed: 0.15,
+            flutter_up: 4.,
+            flutter_right: 16.,
+            jump_speed: 30.,
+            baby_jump_multiplier: 0.7,
+            tall_jump_multiplier: 1.2,
+            jump_cut: 0.5,
+            coyote_time: 0.1,
+            jump_buffer: 0.1,
+            step_height: 4.,
+            max_slope: 50.,
+        }
+    }
+}
+
+/// Values that replace the base tuning for one noun. Anything left out falls back to the
+/// base.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TuningOverride {
+    pub max_x_speed: Option<f32>,
+    pub move_x_acc: Option<f32>,
+    pub fast_multiplier: Option<f32>,
+    pub shrink_speed: Option<f32>,
+    pub flutter_up: Option<f32>,
+    pub flutter_right: Option<f32>,
+    pub jump_speed: Option<f32>,
+    pub baby_jump_multiplier: Option<f32>,
+    pub tall_jump_multiplier: Option<f32>,
+    pub jump_cut: Option<f32>,
+    pub coyote_time: Option<f32>,
+    pub jump_buffer: Option<f32>,
+    pub step_height: Option<f32>,
+    pub max_slope: Option<f32>,
+}
+
+impl TuningOverride {
+    fn apply(&self, base: MovementTuning) -> MovementTuning {
+        MovementTuning {
+            max_x_speed: self.max_x_speed.unwrap_or(base.max_x_speed),
+            move_x_acc: self.move_x_acc.unwrap_or(base.move_x_acc),
+            fast_multiplier: self.fast_multiplier.unwrap_or(base.fast_multiplier),
+            shrink_speed: self.shrink_speed.unwrap_or(base.shrink_speed),
+            flutter_up: self.flutter_up.unwrap_or(base.flutter_up),
+            flutter_right: self.flutter_right.unwrap_or(base.flutter_right),
+            jump_speed: self.jump_speed.unwrap_or(base.jump_speed),
+            baby_jump_multiplier: self.baby_jump_multiplier.unwrap_or(base.baby_jump_multiplier),
+            tall_jump_multiplier: self.tall_jump_multiplier.unwrap_or(base.tall_jump_multiplier),
+            jump_cut: self.jump_cut.unwrap_or(base.jump_cut),
+            coyote_time: self.coyote_time.unwrap_or(base.coyote_time),
+            jump_buffer: self.jump_buffer.unwrap_or(base.jump_buffer),
+            step_height: self.step_height.unwrap_or(base.step_height),
+            max_slope: self.max_slope.unwrap_or(base.max_slope),
+        }
+    }
+}
+
+#[derive(Debug, Default, Asset, TypePath, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MovementConfig {
+    pub base: MovementTuning,
+    pub nouns: HashMap<WordID, TuningOverride>,
+}
+
+impl MovementConfig {
+    pub fn for_noun(&self, noun: WordID) -> MovementTuning {
+        match self.nouns.get(&noun) {
+            Some(tuning_override) => tuning_override.apply(self.base),
+            None => self.base,
+        }
+    }
+}
+
+pub struct MovementConfigLoader;
+impl AssetLoader for MovementConfigLoader {
+    type Asset = MovementConfig;
+    type Settings = ();
+    type Error = std::io::Error;
+
+    fn load<'a>(
+        &self,
+        reader: &'a mut Reader,
+        _: &Self::Settings,
+        _: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<MovementConfig, Self::Error>> {
+        Box::pin(async move {
+            let mut bytes = Vec::new();
+            reader.read_to_end(&mut bytes).await?;
+
+            // an error here keeps the previous config around, so a typo while editing the
+            // file doesn't crash the game.
+            ron::de::from_bytes::<MovementConfig>(&bytes).map_err(|err| {
+                std::io::Error::new(std::io::ErrorKind::InvalidData, err)
+            })
+        })
+    }
+
+    fn extensions(&self) -> &[&str] { &["movement.ron"] }
+}
+
+/// Gives new word objects their tuning, and gives every word object new tuning when the
+/// config file changes.
+pub fn apply_movement_config(
+    mut config_events: EventReader<AssetEvent<MovementConfig>>,
+    configs: Res<Assets<MovementConfig>>,
+    assets: Res<MiscAssets>,
+    word_objects: Query<(&WordObject, Has<MovementTuning>, Entity)>,
+    mut commands: Commands,
+) {
+    let config_changed = config_events.read().any(|event| {
+        event.is_loaded_with_dependencies(&assets.movement) || event.is_modified(&assets.movement)
+    });
+
+    let Some(config) = configs.get(&assets.movement) else { return };
+
+    for (word_object, has_tuning, entity) in &word_objects {
+        if has_tuning && !config_changed { continue }
+        commands.entity(entity).insert(config.for_noun(word_object.noun_word));
+    }
+}
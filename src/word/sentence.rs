@@ -0,0 +1,539 @@
+use itertools::PeekNth;
+
+use crate::prelude::*;
+
+use super::*;
+
+/// Something wrong with the shape of a sentence tree, found by `SentenceStructure::validate`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SentenceError {
+    /// A phrase points to one that isn't in the sentence, or the root is missing.
+    MissingPhrase,
+    /// The sentence isn't a noun phrase with a noun in it.
+    NoNoun,
+    /// A phrase is somewhere its kind can't be, like a noun inside an adjective phrase.
+    Misplaced(PhraseID),
+    /// A word is used as a part of speech it can't be.
+    WrongPartOfSpeech(WordID),
+    /// There are phrases in the map that aren't part of the sentence.
+    Unreachable,
+}
+
+impl SentenceStructure {
+    /// A sentence with no words yet, which is a noun with an empty adjective slot.
+    pub fn new() -> Self {
+        let mut sentence = PhraseMap::default();
+        let adjective = sentence.insert(PhraseData::default());
+        let root = sentence.insert(
+            PhraseData { kind: PhraseKind::Noun { adjective }, ..default() });
+
+        SentenceStructure { sentence, root, valid: false }
+    }
+
+    /// Parses `words` into a sentence. If they don't make one, it's left empty.
+    pub fn from_words(words: &[WordID]) -> Self {
+        let mut sentence = Self::new();
+        sentence.set_words(words);
+        sentence
+    }
+
+    /// Replaces the whole sentence by parsing `words`. If they don't make a sentence, it's
+    /// left empty.
+    pub fn set_words(&mut self, words: &[WordID]) {
+        self.sentence = PhraseMap::default();
+        self.root = self.sentence.insert(PhraseData::default());
+
+        let mut word_iter = itertools::peek_nth(words.iter());
+        self.parse_noun_phrase(&mut word_iter, self.root);
+
+        if !word_iter.is_empty() {
+            self.sentence = PhraseMap::default();
+        }
+    }
+
+    /// Every phrase in the sentence, in the order their words are read.
+    pub fn phrases(&self) -> Vec<PhraseID> {
+        let mut phrases = Vec::new();
+        if self.sentence.contains_key(self.root) {
+            self.push_phrases(self.root, &mut phrases);
+        }
+        phrases
+    }
+
+    /// The words in the sentence, in the order they're read.
+    pub fn words(&self) -> Vec<WordID> {
+        self.phrases().into_iter().filter_map(|id| self.sentence[id].word).collect()
+    }
+
+    /// The words in the sentence that weren't added by the world with `inject_adjective`, in
+    /// the order they're read. These are the ones that go in sentence bars.
+    pub fn own_words(&self) -> Vec<WordID> {
+        self.phrases().into_iter()
+            .map(|id| self.sentence[id])
+            .filter(|phrase| !phrase.injected)
+            .filter_map(|phrase| phrase.word)
+            .collect()
+    }
+
+    /// The adjectives that were added with `inject_adjective`, in the order they're read.
+    pub fn injected_words(&self) -> Vec<WordID> {
+        self.phrases().into_iter()
+            .map(|id| self.sentence[id])
+            .filter(|phrase| phrase.injected && phrase.kind == PhraseKind::Adjective)
+            .filter_map(|phrase| phrase.word)
+            .collect()
+    }
+
+    /// Whether `word` is anywhere in the sentence.
+    pub fn contains_word(&self, word: WordID) -> bool {
+        self.sentence.values().any(|phrase| phrase.word == Some(word))
+    }
+
+    /// The phrases that have `word` in them, in the order they're read.
+    pub fn phrases_of(&self, word: WordID) -> Vec<PhraseID> {
+        self.phrases().into_iter()
+            .filter(|id| self.sentence[*id].word == Some(word))
+            .collect()
+    }
+
+    /// The phrase `id` is part of, or `None` for the root.
+    pub fn parent_of(&self, id: PhraseID) -> Option<PhraseID> {
+        self.sentence.iter()
+            .find(|(_, phrase)| match phrase.kind {
+                PhraseKind::Noun { adjective } => adjective == id,
+                PhraseKind::Combine { l, r } => l == id || r == id,
+                PhraseKind::Adjective => false,
+            })
+            .map(|(parent, _)| parent)
+    }
+
+    /// Adds `word` as an adjective. It goes in the first adjective slot if that's empty, and
+    /// is joined onto the first adjective with "and" if it isn't. Returns the new adjective
+    /// and whether an "and" was made for it, or `None` if the sentence has nowhere to put
+    /// one.
+    pub fn add_adjective(&mut self, word: WordID) -> Option<(PhraseID, bool)> {
+        let id = self.find_phrase(self.root, &|phrase| phrase.kind == PhraseKind::Adjective)?;
+
+        if self.sentence[id].word.is_none() {
+            self.sentence[id].word = Some(word);
+            return Some((id, false));
+        }
+
+        let original = self.sentence[id];
+        let l = self.sentence.insert(PhraseData {
+            word: Some(word),
+            kind: PhraseKind::Adjective,
+            ..default()
+        });
+        let r = self.sentence.insert(original);
+        self.sentence[id] = PhraseData {
+            word: Some(WordID::And),
+            kind: PhraseKind::Combine { l, r },
+            ..default()
+        };
+
+        Some((l, true))
+    }
+
+    /// Adds `word` like `add_adjective`, but as part of the world instead of the player's
+    /// sentence, so it's locked and doesn't show up in sentence bars. Returns whether there
+    /// was somewhere to put it.
+    pub fn inject_adjective(&mut self, word: WordID) -> bool {
+        let Some((id, joined)) = self.add_adjective(word) else { return false };
+
+        // an "and" that was already there, with an empty slot the word went into, belongs
+        // to the player's sentence
+        let parent = self.parent_of(id).filter(|_| joined);
+        for id in std::iter::once(id).chain(parent) {
+            self.sentence[id].injected = true;
+            self.sentence[id].locked = true;
+        }
+
+        true
+    }
+
+    /// Takes out an adjective added with `inject_adjective`, putting back the phrase it was
+    /// joined onto. Returns whether `word` had been injected.
+    pub fn remove_injected_adjective(&mut self, word: WordID) -> bool {
+        let Some(id) = self.phrases_of(word).into_iter()
+            .find(|id| self.sentence[*id].injected)
+            else { return false };
+
+        // it went into an empty slot of an "and" from the player's sentence, which is left
+        // as it was
+        if let Some(parent) = self.parent_of(id) &&
+          !self.sentence[parent].injected &&
+          matches!(self.sentence[parent].kind, PhraseKind::Combine { .. }) {
+            self.sentence[id] = PhraseData::default();
+            return true;
+        }
+
+        self.remove_phrase(id)
+    }
+
+    /// Takes the first adjective with `word` in it out of the sentence. Returns whether there
+    /// was one.
+    pub fn remove_word(&mut self, word: WordID) -> bool {
+        let Some(id) = self.phrases_of(word).into_iter()
+            .find(|id| self.sentence[*id].kind == PhraseKind::Adjective)
+            else { return false };
+
+        self.remove_phrase(id)
+    }
+
+    /// Takes an adjective out of the sentence. If it was one side of an "and", the "and"
+    /// is replaced by the other side. If it was a noun's only adjective, the slot is left
+    /// empty. Returns whether `id` was an adjective that could be taken out.
+    pub fn remove_phrase(&mut self, id: PhraseID) -> bool {
+        if self.sentence.get(id).map(|phrase| phrase.kind) != Some(PhraseKind::Adjective) {
+            return false;
+        }
+
+        let Some(parent) = self.parent_of(id) else { return false };
+        match self.sentence[parent].kind {
+            PhraseKind::Combine { l, r } => {
+                let kept = if l == id { r } else { l };
+                self.sentence[parent] = self.sentence[kept];
+                self.sentence.remove(kept);
+                self.sentence.remove(id);
+            },
+            PhraseKind::Noun { .. } => self.sentence[id] = PhraseData::default(),
+            PhraseKind::Adjective => return false,
+        }
+
+        true
+    }
+
+    /// Puts `word` in place of the word in phrase `id`, keeping the rest of the phrase.
+    pub fn replace_word(&mut self, id: PhraseID, word: WordID) {
+        self.sentence[id].word = Some(word);
+    }
+
+    /// Locks or unlocks every word in the sentence. Injected adjectives stay locked either
+    /// way.
+    pub fn set_locked(&mut self, locked: bool) {
+        for phrase in self.sentence.values_mut() {
+            phrase.locked = locked || phrase.injected;
+        }
+    }
+
+    /// Whether the player's words in the sentence are locked.
+    pub fn is_locked(&self) -> bool {
+        self.sentence.values().any(|phrase| phrase.locked && !phrase.injected)
+    }
+
+    /// Checks that the sentence is a noun phrase whose words are all used as parts of speech
+    /// they can be, and that every phrase in the map is part of it.
+    pub fn validate(&self) -> Result<(), SentenceError> {
+        let root = self.sentence.get(self.root).ok_or(SentenceError::MissingPhrase)?;
+
+        let PhraseData { word: Some(noun), kind: PhraseKind::Noun { adjective }, .. } = *root
+            else { return Err(SentenceError::NoNoun) };
+        if !noun.part_of_speech().contains(&PartOfSpeech::Noun) {
+            return Err(SentenceError::WrongPartOfSpeech(noun));
+        }
+
+        let mut reached = 1;
+        self.validate_adjective_phrase(adjective, &mut reached)?;
+
+        if reached != self.sentence.len() {
+            return Err(SentenceError::Unreachable);
+        }
+
+        Ok(())
+    }
+
+    fn validate_adjective_phrase(
+        &self,
+        id: PhraseID,
+        reached: &mut usize,
+    ) -> Result<(), SentenceError> {
+        let phrase = self.sentence.get(id).ok_or(SentenceError::MissingPhrase)?;
+        *reached += 1;
+
+        let part_of_speech = match phrase.kind {
+            PhraseKind::Noun { .. } => return Err(SentenceError::Misplaced(id)),
+            PhraseKind::Adjective => PartOfSpeech::Adjective,
+            PhraseKind::Combine { l, r } => {
+                self.validate_adjective_phrase(l, reached)?;
+                self.validate_adjective_phrase(r, reached)?;
+                PartOfSpeech::Conjuction
+            },
+        };
+
+        match phrase.word {
+            Some(word) if !word.part_of_speech().contains(&part_of_speech) => {
+                Err(SentenceError::WrongPartOfSpeech(word))
+            },
+            _ => Ok(()),
+        }
+    }
+
+    /// The first phrase under `id` that `filter` matches, looking through nouns' adjectives
+    /// and both sides of combined phrases, left first.
+    fn find_phrase(
+        &self,
+        id: PhraseID,
+        filter: &impl Fn(&PhraseData) -> bool,
+    ) -> Option<PhraseID> {
+        let phrase = self.sentence.get(id)?;
+        if filter(phrase) { return Some(id) }
+
+        match phrase.kind {
+            PhraseKind::Noun { adjective } => self.find_phrase(adjective, filter),
+            PhraseKind::Combine { l, r } => {
+                self.find_phrase(l, filter).or_else(|| self.find_phrase(r, filter))
+            },
+            PhraseKind::Adjective => None,
+        }
+    }
+
+    fn push_phrases(&self, id: PhraseID, phrases: &mut Vec<PhraseID>) {
+        match self.sentence[id].kind {
+            PhraseKind::Noun { adjective } => {
+                self.push_phrases(adjective, phrases);
+                phrases.push(id);
+            },
+            PhraseKind::Adjective => phrases.push(id),
+            PhraseKind::Combine { l, r } => {
+                self.push_phrases(l, phrases);
+                phrases.push(id);
+                self.push_phrases(r, phrases);
+            },
+        }
+    }
+
+    fn parse_noun_phrase(
+        &mut self,
+        words: &mut PeekNth<std::slice::Iter<WordID>>,
+        insert_into: PhraseID,
+    ) {
+        use PartOfSpeech::*;
+
+        if words.is_empty() { return }
+
+        let adjective = self.parse_adjective_phrase(words);
+
+        if part_of_speech(words.peek().copied()).contains(&Noun) {
+            self.sentence[insert_into] = PhraseData {
+                word: Some(*words.next().unwrap()),
+                kind: PhraseKind::Noun { adjective },
+                ..default()
+            }
+        }
+    }
+
+    fn parse_adjective_phrase(
+        &mut self,
+        words: &mut PeekNth<std::slice::Iter<WordID>>,
+    ) -> PhraseID {
+        use PartOfSpeech::*;
+
+        let peek_speech = part_of_speech(words.peek().copied());
+
+        if peek_speech.contains(&Adjective) {
+            if words.peek_nth(1).is_none() && peek_speech.contains(&Noun) {
+                // this is the last word in the sentence - it must be a noun, not an adjective
+                return self.sentence.insert(PhraseData {
+                    word: None,
+                    kind: PhraseKind::Adjective,
+                    ..default()
+                });
+            }
+
+            let adjective_word = words.next().unwrap();
+            let adjective_one = self.sentence.insert(PhraseData {
+                word: Some(*adjective_word),
+                kind: PhraseKind::Adjective,
+                ..default()
+            });
+
+            let conj_word = part_of_speech(words.peek().copied());
+
+            if conj_word.contains(&Conjuction) {
+                let conjunction_word = words.next().unwrap();
+                let adjective_two = self.parse_adjective_phrase(words);
+                self.sentence.insert(PhraseData {
+                    word: Some(*conjunction_word),
+                    kind: PhraseKind::Combine {
+                        l: adjective_one,
+                        r: adjective_two,
+                    },
+                    ..default()
+                })
+            } else {
+                adjective_one
+            }
+        } else if peek_speech.contains(&Conjuction) {
+            let null_adjective = self.sentence.insert(PhraseData {
+                word: None,
+                kind: PhraseKind::Adjective,
+                ..default()
+            });
+
+            let conjunction_word = words.next().unwrap();
+            let adjective_two = self.parse_adjective_phrase(words);
+
+            self.sentence.insert(PhraseData {
+                word: Some(*conjunction_word),
+                kind: PhraseKind::Combine {
+                    l: null_adjective,
+                    r: adjective_two,
+                },
+                ..default()
+            })
+        } else {
+            self.sentence.insert(PhraseData {
+                word: None,
+                kind: PhraseKind::Adjective,
+                ..default()
+            })
+        }
+    }
+}
+
+impl Default for SentenceStructure {
+    fn default() -> Self { Self::new() }
+}
+
+fn part_of_speech(word: Option<&WordID>) -> &'static [PartOfSpeech] {
+    word.copied().map(WordID::part_of_speech).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use WordID::*;
+
+    #[test]
+    fn parses_and_reads_back_words() {
+        for words in [
+            vec![Baby],
+            vec![Wide, Baby],
+            vec![Wide, And, Tall, Horse],
+            vec![Baby, And, Fast, And, Wide, Crate],
+        ] {
+            let sentence = SentenceStructure::from_words(&words);
+            assert_eq!(sentence.words(), words);
+            assert_eq!(sentence.validate(), Ok(()));
+        }
+    }
+
+    #[test]
+    fn rejects_sentences_without_nouns() {
+        assert_eq!(SentenceStructure::new().validate(), Err(SentenceError::NoNoun));
+        assert_eq!(SentenceStructure::from_words(&[]).validate(), Err(SentenceError::NoNoun));
+        assert_eq!(
+            SentenceStructure::from_words(&[Horse, Horse]).validate(),
+            Err(SentenceError::MissingPhrase),
+        );
+    }
+
+    #[test]
+    fn finds_phrases_of_a_word_in_order() {
+        let sentence = SentenceStructure::from_words(&[Wide, And, Wide, Baby]);
+        let phrases = sentence.phrases_of(Wide);
+
+        assert_eq!(phrases.len(), 2);
+        assert_eq!(sentence.phrases()[0], phrases[0]);
+        assert_eq!(sentence.phrases()[2], phrases[1]);
+        assert_eq!(sentence.parent_of(sentence.root), None);
+        assert!(sentence.phrases_of(Tall).is_empty());
+    }
+
+    #[test]
+    fn adds_adjectives_to_empty_slots_and_with_and() {
+        let mut sentence = SentenceStructure::from_words(&[Baby]);
+        sentence.add_adjective(Wide).unwrap();
+        assert_eq!(sentence.words(), [Wide, Baby]);
+
+        sentence.add_adjective(Tall).unwrap();
+        assert_eq!(sentence.words(), [Tall, And, Wide, Baby]);
+        assert_eq!(sentence.validate(), Ok(()));
+    }
+
+    #[test]
+    fn removing_words_collapses_ands() {
+        let mut sentence = SentenceStructure::from_words(&[Wide, And, Tall, And, Fast, Horse]);
+
+        assert!(sentence.remove_word(Tall));
+        assert_eq!(sentence.words(), [Wide, And, Fast, Horse]);
+        assert_eq!(sentence.validate(), Ok(()));
+
+        assert!(sentence.remove_word(Wide));
+        assert_eq!(sentence.words(), [Fast, Horse]);
+
+        assert!(sentence.remove_word(Fast));
+        assert_eq!(sentence.words(), [Horse]);
+        assert_eq!(sentence.validate(), Ok(()));
+
+        assert!(!sentence.remove_word(Fast));
+        assert!(!sentence.remove_word(Horse));
+    }
+
+    #[test]
+    fn injected_adjectives_come_back_out_cleanly() {
+        let mut sentence = SentenceStructure::from_words(&[Wide, And, Tall, Baby]);
+
+        assert!(sentence.inject_adjective(FlutteringUp));
+        assert_eq!(sentence.words(), [FlutteringUp, And, Wide, And, Tall, Baby]);
+        assert_eq!(sentence.own_words(), [Wide, And, Tall, Baby]);
+        assert_eq!(sentence.injected_words(), [FlutteringUp]);
+        assert_eq!(sentence.validate(), Ok(()));
+
+        assert!(sentence.remove_injected_adjective(FlutteringUp));
+        assert_eq!(sentence.words(), [Wide, And, Tall, Baby]);
+        assert_eq!(sentence.validate(), Ok(()));
+
+        assert!(!sentence.remove_injected_adjective(Wide));
+    }
+
+    #[test]
+    fn injecting_into_an_empty_slot_keeps_the_players_and() {
+        let mut sentence = SentenceStructure::from_words(&[And, Wide, Baby]);
+        assert_eq!(sentence.words(), [And, Wide, Baby]);
+
+        assert!(sentence.inject_adjective(Tall));
+        assert_eq!(sentence.words(), [Tall, And, Wide, Baby]);
+        assert_eq!(sentence.own_words(), [And, Wide, Baby]);
+        assert_eq!(sentence.injected_words(), [Tall]);
+
+        assert!(sentence.remove_injected_adjective(Tall));
+        assert_eq!(sentence.words(), [And, Wide, Baby]);
+        assert_eq!(sentence.own_words(), [And, Wide, Baby]);
+    }
+
+    #[test]
+    fn locking_leaves_injected_adjectives_locked() {
+        let mut sentence = SentenceStructure::from_words(&[Wide, Baby]);
+        sentence.inject_adjective(Tall);
+        assert!(!sentence.is_locked());
+
+        sentence.set_locked(true);
+        assert!(sentence.is_locked());
+
+        sentence.set_locked(false);
+        assert!(!sentence.is_locked());
+        assert!(sentence.phrases_of(Tall).iter().all(|id| sentence.sentence[*id].locked));
+    }
+
+    #[test]
+    fn replaced_words_are_checked_by_validate() {
+        let mut sentence = SentenceStructure::from_words(&[Wide, Baby]);
+        let wide = sentence.phrases_of(Wide)[0];
+
+        sentence.replace_word(wide, Tall);
+        assert_eq!(sentence.words(), [Tall, Baby]);
+        assert_eq!(sentence.validate(), Ok(()));
+
+        sentence.replace_word(wide, Horse);
+        assert_eq!(sentence.validate(), Err(SentenceError::WrongPartOfSpeech(Horse)));
+    }
+
+    #[test]
+    fn finds_unreachable_phrases() {
+        let mut sentence = SentenceStructure::from_words(&[Baby]);
+        sentence.sentence.insert(PhraseData::default());
+        assert_eq!(sentence.validate(), Err(SentenceError::Unreachable));
+    }
+}
@@ -24,6 +24,19 @@ pub struct AdjectiveStates {
     pub fluttering: Option<FlutteringDirection>,
 }
 
+impl AdjectiveStates {
+    /// The scale a word object grows or shrinks towards with these adjectives.
+    pub fn target_scale(&self) -> Vec2 {
+        let mut target_scale = Vec2::ONE;
+
+        if self.tall { target_scale.y *= 4.; }
+        if self.wide { target_scale.x *= 4.; }
+        if self.baby { target_scale *= 0.5; }
+
+        target_scale
+    }
+}
+
 #[derive(Bundle, Clone, Default)]
 pub struct WordObjectBundle {
     sprite: Sprite,
@@ -67,18 +80,32 @@ pub fn remake_player_character(
 
                 command_closure(&mut commands);
 
-                all_sprites.for_each_mut(|mut sprite| sprite.color = Color::WHITE);
+                set_sprite_colors(sentence.1, Color::WHITE, &children, &mut all_sprites);
                 spawn_events.send(SentenceSpawn);
             },
             Err(_) => {
-                all_sprites
-                    .for_each_mut(|mut sprite| sprite.color = Color::GRAY.with_a(0.2));
+                let faded = Color::GRAY.with_a(0.2);
+                set_sprite_colors(sentence.1, faded, &children, &mut all_sprites);
                 sentence.0.valid = false;
             }
         }
     }
 }
 
+/// Colors the sprites of the word objects that make up a sentence.
+fn set_sprite_colors(
+    sentence: Entity,
+    color: Color,
+    children: &Query<&Children>,
+    sprites: &mut Query<&mut Sprite>,
+) {
+    for child in children.iter_descendants(sentence) {
+        if let Ok(mut sprite) = sprites.get_mut(child) {
+            sprite.color = color;
+        }
+    }
+}
+
 #[derive(Copy, Debug, Clone)]
 pub enum FlutteringDirection { Up, Down, Left, Right }
 
@@ -150,7 +177,8 @@ fn spawn_with_noun(
                                 (
                                     Collider::cuboid(32.0, 8.0), 
                                     CollidingEntities::default(),
-                                    ColliderMassProperties::Density(0.5),
+                                    // heavier than water, so horses sink
+                                    ColliderMassProperties::Density(1.0),
                                     ActiveEvents::all(),
                                 ),
                                 Name::new("Horse"),
@@ -160,6 +188,27 @@ fn spawn_with_noun(
                         new.insert(word_object).set_parent(parent);
                     })
                 }
+                WordID::Crate => {
+                    let square_yellow = assets.square_yellow.clone();
+                    Box::new(move |commands| {
+                        let mut new = if let Some(existing) = existing_noun {
+                            commands.entity(existing)
+                        } else {
+                            commands.spawn((
+                                WordObjectBundle { texture: square_yellow, ..default() },
+                                (
+                                    Collider::cuboid(8.0, 8.0), 
+                                    CollidingEntities::default(),
+                                    ColliderMassProperties::Mass(60.),
+                                    ActiveEvents::all(),
+                                ),
+                                Name::new("Crate"),
+                            ))
+                        };
+
+                        new.insert(word_object).set_parent(parent);
+                    })
+                },
                 _ => return Err(SentenceParseError::Other),
             };
 
@@ -72,7 +72,8 @@ pub fn do_drag(
 
 pub fn do_snap(
     mut draggables: Query<QDraggableWord, With<Dragging>>,
-    sentence_ui_parents: Query<(&Node, &GlobalTransform, Entity), With<SentenceUIParent>>, 
+    sentence_ui_parents: Query<(&Node, &GlobalTransform, &SentenceUIParent, Entity)>,
+    sentences: Query<&SentenceStructure>,
     inventory: Query<Entity, With<Inventory>>,
     mouse: Res<Input<MouseButton>>,
     mut commands: Commands,
@@ -85,10 +86,15 @@ pub fn do_snap(
     for mut draggable in &mut draggables {
         let new_parent = sentence_ui_parents
             .iter()
+            .filter(|ui_parent| {
+                // locked sentences can't take new words either
+                sentences.get(ui_parent.2.sentence_entity)
+                    .map_or(true, |sentence| !sentence.is_locked())
+            })
             .find_map(|ui_parent| {
                 let rect = ui_parent.0.logical_rect(ui_parent.1);
                 rect.contains(draggable.global_transform.translation().xy())
-                    .then_some(ui_parent.2)
+                    .then_some(ui_parent.3)
             })
             .unwrap_or(inventory);
 
@@ -121,6 +127,8 @@ pub fn do_unsnap(
                 commands.entity(draggable.entity)
                     .insert(Dragging)
                     .set_parent(drag_parent.single());
+            } else if draggable.draggable.locked {
+                continue;
             } else {
                 commands.entity(draggable.entity)
                     .set_parent(inventory);
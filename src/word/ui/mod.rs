@@ -4,7 +4,6 @@ use bevy::ecs::query::{WorldQuery, ReadOnlyWorldQuery};
 use bevy::ecs::system::SystemParam;
 use bevy::input::mouse::MouseMotion;
 use bevy::ui::Interaction;
-use itertools::PeekNth;
 
 use crate::load_assets::MiscAssets;
 
@@ -22,15 +21,22 @@ pub struct DraggableWord {
 #[derive(Component)]
 pub struct SentenceUIParent {
     sentence_entity: Entity,
+    /// The noun at the end of the sentence that isn't in the UI, for objects that are always
+    /// the same thing.
+    implied_noun: Option<WordID>,
 }
 
-#[derive(Debug, Component)]
-pub struct SentenceSection {
-    pub for_phrase: PhraseID,
-    pub sentence_entity: Entity,
-    pub locked: bool,
+/// Gives an object other than the player its own sentence bar, which floats above it. The
+/// object is always `noun`, so only the words describing it go in the bar.
+#[derive(Component)]
+pub struct OwnSentenceBar {
+    pub noun: WordID,
 }
 
+/// A sentence bar that belongs to an object with an `OwnSentenceBar`.
+#[derive(Component)]
+pub struct FloatingSentenceBar;
+
 #[derive(Debug, Component)]
 pub struct SentenceJoint;
 
@@ -146,6 +152,7 @@ pub fn setup_word_ui(
     let _word_snap_parent = commands.spawn((
         SentenceUIParent {
             sentence_entity: player.0,
+            implied_noun: None,
         },
         NodeBundle {
             style: Style {
@@ -192,6 +199,176 @@ pub fn update_vocabulary(
     }
 }
 
+const FLOATING_BAR_SIZE: Vec2 = Vec2::new(480., 80.);
+
+pub fn spawn_floating_sentence_bars(
+    objects: Query<(&OwnSentenceBar, &SentenceStructure, Entity), Added<OwnSentenceBar>>,
+    assets: Res<MiscAssets>,
+    mut commands: Commands,
+) {
+    for (own_bar, sentence, entity) in &objects {
+        let bar = commands.spawn((
+            SentenceUIParent { sentence_entity: entity, implied_noun: Some(own_bar.noun) },
+            FloatingSentenceBar,
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    display: Display::None,
+                    justify_content: JustifyContent::Center,
+                    width: Val::Px(FLOATING_BAR_SIZE.x),
+                    height: Val::Px(FLOATING_BAR_SIZE.y),
+                    column_gap: Val::Px(20.0),
+                    ..default()
+                },
+                background_color: Color::BLUE.with_a(0.2).into(),
+                ..default()
+            },
+            Name::new("Floating Sentence Bar"),
+        )).id();
+
+        // the words the object already has, like ones it was saved with
+        let mut words = sentence.own_words();
+        if words.last() == Some(&own_bar.noun) {
+            words.pop();
+        }
+        for word in words {
+            commands.spawn(DraggableWordBundle::for_word_snapped(word, &*assets)).set_parent(bar);
+        }
+    }
+}
+
+/// Keeps floating sentence bars above their objects, and removes them with their objects.
+/// The words in the bar go with it, since the object's sentence remembers them.
+pub fn place_floating_sentence_bars(
+    mut bars: Query<(&SentenceUIParent, &mut Style, Entity), With<FloatingSentenceBar>>,
+    objects: Query<&GlobalTransform, With<OwnSentenceBar>>,
+    cameras: Query<(&Camera, &GlobalTransform), With<Camera2d>>,
+    mut commands: Commands,
+) {
+    let (camera, camera_transform) = cameras.single();
+    let Some(viewport) = camera.logical_viewport_size() else { return };
+
+    for (bar, mut style, entity) in &mut bars {
+        let Ok(object) = objects.get(bar.sentence_entity) else {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        };
+
+        let above = object.translation() + Vec3::Y * 24.;
+        let on_screen = camera.world_to_viewport(camera_transform, above)
+            .filter(|position| Rect::from_corners(Vec2::ZERO, viewport).contains(*position));
+
+        if let Some(position) = on_screen {
+            style.display = Display::Flex;
+            style.left = Val::Px(position.x - FLOATING_BAR_SIZE.x / 2.);
+            style.top = Val::Px(position.y - FLOATING_BAR_SIZE.y);
+        } else {
+            style.display = Display::None;
+        }
+    }
+}
+
+/// A copy of a sentence, the order of the words in its UI, and the vocabulary it was built
+/// from. Used to put a sentence back the way it was, e.g. when the player dies.
+#[derive(Debug, Clone)]
+pub struct SentenceSnapshot {
+    structure: SentenceStructure,
+    words: Vec<WordID>,
+    vocabulary: HashSet<WordID>,
+}
+
+#[derive(Event)]
+pub struct RestoreSentence {
+    pub on: Entity,
+    pub snapshot: SentenceSnapshot,
+}
+
+#[derive(SystemParam)]
+pub struct SentenceSnapshots<'w, 's> {
+    sentences: Query<'w, 's, (&'static SentenceStructure, &'static Vocabulary)>,
+    ui_parents: Query<'w, 's, (&'static SentenceUIParent, Option<&'static Children>)>,
+    words: Query<'w, 's, &'static DraggableWord>,
+}
+
+impl<'w, 's> SentenceSnapshots<'w, 's> {
+    pub fn take(&self, sentence_entity: Entity) -> Option<SentenceSnapshot> {
+        let (structure, vocabulary) = self.sentences.get(sentence_entity).ok()?;
+
+        let words = self.ui_parents.iter()
+            .filter(|ui_parent| ui_parent.0.sentence_entity == sentence_entity)
+            .filter_map(|ui_parent| ui_parent.1)
+            .flat_map(|children| children.iter())
+            .filter_map(|child| self.words.get(*child).ok())
+            .map(|word| word.word_id)
+            .collect();
+
+        Some(SentenceSnapshot {
+            structure: structure.clone(),
+            words,
+            vocabulary: vocabulary.words.clone(),
+        })
+    }
+}
+
+pub fn restore_sentences(
+    mut restores: EventReader<RestoreSentence>,
+    mut sentences: Query<(&mut SentenceStructure, &mut Vocabulary)>,
+    ui_parents: Query<(&SentenceUIParent, Entity)>,
+    inventory: Query<Entity, With<Inventory>>,
+    mut words: Query<(&DraggableWord, &mut Style, &Parent, Entity)>,
+    mut structure_changes: EventWriter<SentenceStructureChanged>,
+    mut commands: Commands,
+) {
+    let inventory = inventory.single();
+
+    for restore in restores.read() {
+        let Ok((mut structure, mut vocabulary)) = sentences.get_mut(restore.on)
+            else { continue };
+        let snapshot = &restore.snapshot;
+
+        *structure = snapshot.structure.clone();
+        vocabulary.words.retain(|word| snapshot.vocabulary.contains(word));
+
+        let mut unplaced: Vec<Entity> = Vec::new();
+        for (word, mut style, parent, entity) in &mut words {
+            // words that were given to other objects stay with them
+            if let Ok(ui_parent) = ui_parents.get(parent.get()) &&
+              ui_parent.0.sentence_entity != restore.on {
+                continue;
+            }
+
+            if !snapshot.vocabulary.contains(&word.word_id) {
+                commands.entity(entity).despawn_recursive();
+                continue;
+            }
+
+            style.left = Val::Auto;
+            style.top = Val::Auto;
+            style.position_type = PositionType::Relative;
+            commands.entity(entity).remove::<Dragging>();
+            unplaced.push(entity);
+        }
+
+        let mut in_sentence = Vec::new();
+        for word_id in &snapshot.words {
+            let Some(index) = unplaced.iter()
+                .position(|entity| words.get(*entity).unwrap().0.word_id == *word_id)
+                else { continue };
+            in_sentence.push(unplaced.remove(index));
+        }
+
+        for entity in unplaced {
+            commands.entity(entity).set_parent(inventory);
+        }
+
+        for ui_parent in ui_parents.iter().filter(|p| p.0.sentence_entity == restore.on) {
+            commands.entity(ui_parent.1).replace_children(&in_sentence);
+        }
+
+        structure_changes.send(SentenceStructureChanged { on: restore.on });
+    }
+}
+
 #[derive(WorldQuery)]
 #[world_query(mutable)]
 pub struct QSentenceNode {
@@ -217,20 +394,26 @@ pub fn reorder_sentence_ui(
     }
 }
 
-pub fn indicate_sentence_section_locks(
-    sections: Query<(&mut SentenceSection, Option<&Children>), Changed<SentenceSection>>,
+/// Grays out the words in the sentence bars of locked sentences, and keeps them from being
+/// dragged out.
+pub fn indicate_sentence_locks(
+    sentences: Query<&SentenceStructure, Changed<SentenceStructure>>,
+    ui_parents: Query<(&SentenceUIParent, &Children)>,
     mut words: Query<(&mut DraggableWord, &mut Text)>,
 ) {
-    for (section, children) in &sections {
-        let Some(children) = children else { continue };
-        let Some(first_child) = children.get(0) else { continue };
-        let Ok(mut word) = words.get_mut(*first_child) else { continue };
-
-        word.0.locked = section.locked;
-        if section.locked {
-            word.1.sections[0].style.color = Color::GRAY;
-        } else {
-            word.1.sections[0].style.color = Color::WHITE;
+    for (ui_parent, children) in &ui_parents {
+        let Ok(sentence) = sentences.get(ui_parent.sentence_entity) else { continue };
+        let locked = sentence.is_locked();
+
+        for child in children {
+            let Ok(mut word) = words.get_mut(*child) else { continue };
+
+            word.0.locked = locked;
+            if locked {
+                word.1.sections[0].style.color = Color::GRAY;
+            } else {
+                word.1.sections[0].style.color = Color::WHITE;
+            }
         }
     }
 }
@@ -246,7 +429,7 @@ pub fn regenerate_sentence_structure(
         let Ok(ui_parent) = sentence_ui_parents.get_mut(ui_change.ui_parent)
             else { continue };
 
-        let words: Vec<WordID> = if let Some(mut word_objects) = ui_parent.1 {
+        let mut words: Vec<WordID> = if let Some(mut word_objects) = ui_parent.1 {
             word_objects.sort_by_key(|entity| {
                 if *entity == ui_change.word_entity {
                     ui_change.word_pos.x as u32
@@ -263,112 +446,21 @@ pub fn regenerate_sentence_structure(
             Vec::new()
         };
 
+        words.extend(ui_parent.0.implied_noun);
+
         let (sentence_entity, mut sentence) = 
             sentences.get_mut(ui_parent.0.sentence_entity).unwrap();
 
-        sentence.sentence = PhraseMap::default();
-        let root = sentence.sentence.insert(PhraseData::default());
-        sentence.root = root;
-
-        let mut word_iter = itertools::peek_nth(words.iter());
-        parse_noun_phrase(&mut word_iter, &mut *sentence, root);
+        // what the world did to the sentence isn't in the UI, so it's carried over
+        let injected = sentence.injected_words();
+        let locked = sentence.is_locked();
 
-        if !word_iter.is_empty() {
-            sentence.sentence = PhraseMap::default();
+        sentence.set_words(&words);
+        for word in injected {
+            sentence.inject_adjective(word);
         }
+        sentence.set_locked(locked);
 
         structure_changes.send(SentenceStructureChanged { on: sentence_entity });
     }
 }
-
-fn parse_noun_phrase(
-    words: &mut PeekNth<std::slice::Iter<word_id::WordID>>,
-    sentence: &mut SentenceStructure,
-    insert_into: PhraseID,
-) {
-    use PartOfSpeech::*;
-
-    if words.is_empty() { return }
-
-    let adjective = parse_adjective_phrase(words, sentence);
-
-    if part_of_speech(words.peek().copied()).contains(&Noun) {
-        sentence.sentence[insert_into] = PhraseData {
-            word: Some(*words.next().unwrap()),
-            kind: PhraseKind::Noun { adjective },
-            ..default()
-        }
-    }
-}
-
-fn parse_adjective_phrase(
-    words: &mut PeekNth<std::slice::Iter<word_id::WordID>>,
-    sentence: &mut SentenceStructure,
-) -> PhraseID {
-    use PartOfSpeech::*;
-
-    let peek_speech = part_of_speech(words.peek().copied()); 
-
-    if peek_speech.contains(&Adjective) {
-        if words.peek_nth(1).is_none() && peek_speech.contains(&Noun) {
-            // this is the last word in the sentence - it must be a noun, not an adjective
-            return sentence.sentence.insert(PhraseData {
-                word: None,
-                kind: PhraseKind::Adjective,
-                ..default()
-            });
-        }
-
-        let adjective_word = words.next().unwrap();
-        let adjective_one = sentence.sentence.insert(PhraseData {
-            word: Some(*adjective_word),
-            kind: PhraseKind::Adjective,
-            ..default()
-        });
-
-        let conj_word = part_of_speech(words.peek().copied());
-
-        if conj_word.contains(&Conjuction) {
-            let conjunction_word = words.next().unwrap();
-            let adjective_two = parse_adjective_phrase(words, sentence);
-            sentence.sentence.insert(PhraseData {
-                word: Some(*conjunction_word),
-                kind: PhraseKind::Combine {
-                    l: adjective_one,
-                    r: adjective_two,
-                },
-                ..default()
-            })
-        } else {
-            adjective_one
-        }
-    } else if peek_speech.contains(&Conjuction) {
-        let null_adjective = sentence.sentence.insert(PhraseData {
-            word: None,
-            kind: PhraseKind::Adjective,
-            ..default()
-        });
-
-        let conjunction_word = words.next().unwrap();
-        let adjective_two = parse_adjective_phrase(words, sentence);
-
-        sentence.sentence.insert(PhraseData {
-            word: Some(*conjunction_word),
-            kind: PhraseKind::Combine {
-                l: null_adjective,
-                r: adjective_two,
-            },
-            ..default()
-        })
-    } else {
-        sentence.sentence.insert(PhraseData {
-            word: None,
-            kind: PhraseKind::Adjective,
-            ..default()
-        })
-    }
-}
-
-fn part_of_speech(word: Option<&WordID>) -> &'static [PartOfSpeech] {
-    word.copied().map(WordID::part_of_speech).unwrap_or_default()
-}
@@ -14,6 +14,9 @@ pub enum WordID {
     And,
     FlutteringUp,
     FlutteringRight,
+    /// Only ever the noun of a crate's sentence, so it's never in the player's vocabulary and
+    /// isn't in `ALL_WORDS`.
+    Crate,
 }
 
 impl WordID {
@@ -31,6 +34,7 @@ impl WordID {
             WordID::And => word("And", "and"),
             WordID::FlutteringUp => word("Fluttering", "fluttering_up"),
             WordID::FlutteringRight => word("Fluttering", "fluttering_right"),
+            WordID::Crate => word("Crate", "crate"),
         }
     }
 
@@ -46,6 +50,7 @@ impl WordID {
             WordID::And => &[Conjuction],
             WordID::FlutteringUp => &[Adjective],
             WordID::FlutteringRight => &[Adjective],
+            WordID::Crate => &[Noun],
         }
     }
 }
@@ -0,0 +1,239 @@
+// Merges tiles into as few collider rectangles as possible. This only works in tile
+// coordinates and doesn't touch the ECS or physics, so it can be tested on its own. The
+// coordinates follow the convention in `tile_coord`.
+
+use grid::Grid;
+
+use super::tile_coord::{TileCoord, TileRect};
+
+/// Tracks which tiles of a region already belong to a collider.
+pub struct Covered {
+    region: TileRect,
+    grid: Grid<bool>,
+}
+
+impl Covered {
+    pub fn new(region: TileRect) -> Self {
+        Self { region, grid: Grid::new(region.width(), region.height()) }
+    }
+
+    /// Marks the part of `rect` that's inside the region.
+    pub fn mark(&mut self, rect: TileRect) {
+        for coord in rect.iter().filter(|coord| self.region.contains(*coord)) {
+            let local = self.local(coord);
+            self.grid[local] = true;
+        }
+    }
+
+    pub fn is_covered(&self, coord: TileCoord) -> bool {
+        self.grid[self.local(coord)]
+    }
+
+    fn local(&self, coord: TileCoord) -> TileCoord {
+        TileCoord::new(
+            coord.x - self.region.min.x as usize,
+            coord.y - self.region.min.y as usize,
+        )
+    }
+}
+
+/// Greedily merges the tiles in `region` that `solid` is true for into rectangles, growing
+/// each one along `x` first and then along `y`. Tiles that are already covered are skipped,
+/// and the new rectangles are marked as covered.
+pub fn merge_rects(
+    region: TileRect,
+    covered: &mut Covered,
+    solid: impl Fn(TileCoord) -> bool,
+) -> Vec<TileRect> {
+    let mut output = Vec::new();
+    let free = |covered: &Covered, x, y| {
+        let coord = TileCoord::new(x, y);
+        solid(coord) && !covered.is_covered(coord)
+    };
+
+    let (max_x, max_y) = (region.max.x as usize, region.max.y as usize);
+
+    for TileCoord { x: start_x, y: start_y } in region.iter() {
+        if !free(covered, start_x, start_y) { continue }
+
+        let mut x = start_x;
+        while x < max_x && free(covered, x, start_y) {
+            x += 1;
+        }
+
+        let mut y = start_y;
+        while y < max_y && (start_x..x).all(|x| free(covered, x, y)) {
+            y += 1;
+        }
+
+        let rect = TileRect::new((start_x, start_y), (x, y));
+        covered.mark(rect);
+        output.push(rect);
+    }
+
+    output
+}
+
+/// Like `merge_rects`, but the rectangles are only ever one tile tall.
+pub fn merge_runs(
+    region: TileRect,
+    covered: &mut Covered,
+    solid: impl Fn(TileCoord) -> bool,
+) -> Vec<TileRect> {
+    let mut output = Vec::new();
+    let free = |covered: &Covered, x, y| {
+        let coord = TileCoord::new(x, y);
+        solid(coord) && !covered.is_covered(coord)
+    };
+
+    for y in region.min.y as usize..region.max.y as usize {
+        let mut x = region.min.x as usize;
+        while x < region.max.x as usize {
+            if !free(covered, x, y) { x += 1; continue }
+
+            let start_x = x;
+            while x < region.max.x as usize && free(covered, x, y) {
+                x += 1;
+            }
+
+            let rect = TileRect::new((start_x, y), (x, y + 1));
+            covered.mark(rect);
+            output.push(rect);
+        }
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::Vec2;
+
+    use super::*;
+
+    /// A tiny linear congruential generator, so the tests are repeatable without pulling in
+    /// a random number crate.
+    struct Lcg(u64);
+
+    impl Lcg {
+        fn next(&mut self) -> u64 {
+            self.0 = self.0.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            self.0 >> 33
+        }
+
+        fn below(&mut self, n: u64) -> usize {
+            (self.next() % n) as usize
+        }
+    }
+
+    fn random_grid(rng: &mut Lcg, fill_percent: usize) -> Grid<bool> {
+        let (rows, cols) = (1 + rng.below(24), 1 + rng.below(24));
+        let mut grid = Grid::new(rows, cols);
+        for x in 0..rows {
+            for y in 0..cols {
+                grid[(x, y)] = rng.below(100) < fill_percent;
+            }
+        }
+        grid
+    }
+
+    /// Checks that `rects` cover every solid tile in `region` exactly once, and nothing else.
+    fn assert_exact_cover(grid: &Grid<bool>, region: TileRect, rects: &[TileRect]) {
+        let mut count = Grid::<u32>::new(grid.rows(), grid.cols());
+        for rect in rects {
+            assert!(rect.width() > 0 && rect.height() > 0, "empty rect {rect:?}");
+            for coord in rect.iter() {
+                assert!(region.contains(coord), "{rect:?} is outside of {region:?}");
+                count[coord] += 1;
+            }
+        }
+
+        for coord in TileRect::of_grid(grid).iter() {
+            let expected = (grid[coord] && region.contains(coord)) as u32;
+            assert_eq!(count[coord], expected, "tile {coord:?} in {rects:?}");
+        }
+    }
+
+    #[test]
+    fn rects_exactly_cover_random_grids() {
+        let mut rng = Lcg(0x5eed);
+        for _ in 0..500 {
+            let fill = rng.below(101);
+            let grid = random_grid(&mut rng, fill);
+            let region = TileRect::of_grid(&grid);
+
+            let rects = merge_rects(region, &mut Covered::new(region), |coord| grid[coord]);
+            assert_exact_cover(&grid, region, &rects);
+        }
+    }
+
+    #[test]
+    fn runs_exactly_cover_random_grids() {
+        let mut rng = Lcg(0xfeed);
+        for _ in 0..500 {
+            let fill = rng.below(101);
+            let grid = random_grid(&mut rng, fill);
+            let region = TileRect::of_grid(&grid);
+
+            let runs = merge_runs(region, &mut Covered::new(region), |coord| grid[coord]);
+            assert_exact_cover(&grid, region, &runs);
+            assert!(runs.iter().all(|run| run.height() == 1));
+        }
+    }
+
+    #[test]
+    fn rects_stay_inside_sub_regions() {
+        let mut rng = Lcg(0xbead);
+        for _ in 0..500 {
+            let grid = random_grid(&mut rng, 60);
+            let whole = TileRect::of_grid(&grid);
+            let min = (rng.below(whole.max.x as u64), rng.below(whole.max.y as u64));
+            let max = (
+                min.0 + 1 + rng.below(whole.max.x as u64 - min.0 as u64),
+                min.1 + 1 + rng.below(whole.max.y as u64 - min.1 as u64),
+            );
+            let region = TileRect::new(min, max);
+
+            let rects = merge_rects(region, &mut Covered::new(region), |coord| grid[coord]);
+            assert_exact_cover(&grid, region, &rects);
+        }
+    }
+
+    #[test]
+    fn covered_tiles_are_skipped() {
+        let grid = Grid::init(6, 6, true);
+        let region = TileRect::of_grid(&grid);
+        let mut covered = Covered::new(region);
+        covered.mark(TileRect::new((2, 2), (4, 4)));
+
+        let rects = merge_rects(region, &mut covered, |coord| grid[coord]);
+
+        let mut all = rects.clone();
+        all.push(TileRect::new((2, 2), (4, 4)));
+        assert_exact_cover(&grid, region, &all);
+    }
+
+    #[test]
+    fn full_grid_is_one_rect() {
+        let grid = Grid::init(5, 3, true);
+        let region = TileRect::of_grid(&grid);
+
+        let rects = merge_rects(region, &mut Covered::new(region), |coord| grid[coord]);
+        assert_eq!(rects, vec![TileRect::new((0, 0), (5, 3))]);
+    }
+
+    #[test]
+    fn x_is_the_row_index() {
+        // a grid with 4 rows and 1 column is a horizontal strip of 4 tiles.
+        let grid = Grid::init(4, 1, true);
+        let region = TileRect::of_grid(&grid);
+        assert_eq!(region, TileRect::new((0, 0), (4, 1)));
+        assert!(TileCoord::new(3, 0).in_grid(&grid));
+        assert!(!TileCoord::new(0, 3).in_grid(&grid));
+
+        let rects = merge_rects(region, &mut Covered::new(region), |coord| grid[coord]);
+        let level_rect = rects[0].level_rect();
+        assert_eq!(level_rect.min, Vec2::new(-8., -8.));
+        assert_eq!(level_rect.max, Vec2::new(56., 8.));
+    }
+}
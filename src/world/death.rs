@@ -0,0 +1,151 @@
+// Everything that happens after the player dies: counting deaths, and putting the sentence
+// and collected words back the way they were when the player entered the level.
+
+use std::path::PathBuf;
+
+use crate::{prelude::*, word::ui::{SentenceSnapshot, SentenceSnapshots, RestoreSentence}};
+
+use super::{CurrentLevel, LoadedLevel, objects::{WordTag, SentenceReset}};
+
+#[derive(Event)]
+pub struct PlayerDied {
+    pub level: Option<Entity>,
+    pub sentence_reset: SentenceReset,
+}
+
+/// How many times the player has died in each level, keyed by the level's asset path.
+#[derive(Resource, Default, Debug)]
+pub struct DeathCounter {
+    pub per_level: HashMap<PathBuf, u32>,
+}
+
+impl DeathCounter {
+    pub fn in_level(&self, level_path: &PathBuf) -> u32 {
+        self.per_level.get(level_path).copied().unwrap_or(0)
+    }
+
+    pub fn total(&self) -> u32 {
+        self.per_level.values().sum()
+    }
+}
+
+/// The player's sentence and the word tags that were already collected when the player
+/// entered the current level.
+#[derive(Resource, Default)]
+pub struct LevelStart {
+    sentence: Option<SentenceSnapshot>,
+    hidden_tags: HashSet<Entity>,
+}
+
+#[derive(Component)]
+pub struct DeathCounterText;
+
+fn level_path(
+    level: Option<Entity>,
+    levels: &Query<&LoadedLevel>,
+    asset_server: &AssetServer,
+) -> PathBuf {
+    level
+        .and_then(|level| levels.get(level).ok())
+        .and_then(|level| asset_server.get_path(level.handle.id()))
+        .map(|path| path.path().to_path_buf())
+        .unwrap_or_default()
+}
+
+pub fn snapshot_level_start(
+    current_level: Res<CurrentLevel>,
+    player: Query<Entity, With<Player>>,
+    snapshots: SentenceSnapshots,
+    tags: Query<(&Visibility, Entity), With<WordTag>>,
+    mut level_start: ResMut<LevelStart>,
+) {
+    if !current_level.is_changed() { return }
+
+    level_start.sentence = snapshots.take(player.single());
+    level_start.hidden_tags = tags.iter()
+        .filter(|tag| *tag.0 == Visibility::Hidden)
+        .map(|tag| tag.1)
+        .collect();
+}
+
+pub fn count_deaths(
+    mut deaths: EventReader<PlayerDied>,
+    levels: Query<&LoadedLevel>,
+    asset_server: Res<AssetServer>,
+    mut counter: ResMut<DeathCounter>,
+) {
+    for death in deaths.read() {
+        let path = level_path(death.level, &levels, &*asset_server);
+        let count = counter.per_level.entry(path.clone()).or_default();
+        *count += 1;
+
+        info!("player died in {path:?} ({count} deaths there so far)");
+    }
+}
+
+pub fn reset_on_death(
+    mut deaths: EventReader<PlayerDied>,
+    level_start: Res<LevelStart>,
+    player: Query<Entity, With<Player>>,
+    mut tags: Query<(&mut Visibility, Entity), With<WordTag>>,
+    mut restores: EventWriter<RestoreSentence>,
+) {
+    for death in deaths.read() {
+        if death.sentence_reset != SentenceReset::LevelStart { continue }
+
+        for (mut visibility, tag) in &mut tags {
+            if *visibility == Visibility::Hidden && !level_start.hidden_tags.contains(&tag) {
+                *visibility = Visibility::Inherited;
+            }
+        }
+
+        if let Some(snapshot) = &level_start.sentence {
+            restores.send(RestoreSentence { on: player.single(), snapshot: snapshot.clone() });
+        }
+    }
+}
+
+pub fn shake_on_death(mut deaths: EventReader<PlayerDied>, mut shakes: EventWriter<CameraShake>) {
+    for _ in deaths.read() {
+        shakes.send(CameraShake { trauma: 0.6 });
+    }
+}
+
+pub fn setup_death_counter_ui(mut commands: Commands, assets: Res<MiscAssets>) {
+    commands.spawn((
+        TextBundle {
+            text: Text::from_section(
+                "",
+                TextStyle {
+                    font: assets.font.clone(),
+                    font_size: 30.0,
+                    color: Color::BLACK,
+                },
+            ),
+            style: Style {
+                position_type: PositionType::Absolute,
+                left: Val::Px(10.),
+                bottom: Val::Px(10.),
+                ..default()
+            },
+            ..default()
+        },
+        DeathCounterText,
+        Name::new("Death Counter"),
+    ));
+}
+
+pub fn update_death_counter_ui(
+    counter: Res<DeathCounter>,
+    current_level: Res<CurrentLevel>,
+    levels: Query<&LoadedLevel>,
+    asset_server: Res<AssetServer>,
+    mut texts: Query<&mut Text, With<DeathCounterText>>,
+) {
+    if !counter.is_changed() && !current_level.is_changed() { return }
+
+    let path = level_path(current_level.entity, &levels, &*asset_server);
+    for mut text in &mut texts {
+        text.sections[0].value = format!("deaths: {}", counter.in_level(&path));
+    }
+}
@@ -1,13 +1,15 @@
 use std::f32::consts::PI;
 
 use bevy_ecs_tilemap::prelude::*;
-use crate::{prelude::*, word::WordID, world::helpers::level_is_in_position};
+use crate::{prelude::*, world::helpers::level_is_in_position};
 use bevy::window::*;
+use bevy::ecs::world::EntityRef;
 use bevy_rapier2d::prelude::shape_views::CuboidView;
 
 mod dropdown;
 use dropdown::*;
 use super::*;
+use super::tile_coord::{TILE_SIZE, to_level_space, tilemap_size};
 
 pub struct WorldEditorPlugin;
 impl Plugin for WorldEditorPlugin {
@@ -17,7 +19,19 @@ impl Plugin for WorldEditorPlugin {
             .add_systems(Update, (
                 set_mouse_world_coords,
                 open_world_editor,
-                edit_world
+                (
+                    edit_world,
+                    draw_camera_zones,
+                    draw_platform_paths,
+                    draw_force_zones,
+                    (edit_zone_areas::<LockZone>, draw_zone_areas::<LockZone>),
+                    (edit_zone_areas::<DeathZone>, draw_zone_areas::<DeathZone>),
+                    (edit_zone_areas::<Fan>, draw_zone_areas::<Fan>),
+                    (edit_zone_areas::<WaterZone>, draw_zone_areas::<WaterZone>),
+                    (edit_zone_areas::<ForceZone>, draw_zone_areas::<ForceZone>),
+                    (edit_zone_areas::<WordZone>, draw_zone_areas::<WordZone>),
+                    find_object_links.pipe(draw_object_links),
+                )
                     .after(setup_world_editor_gui)
                     .run_if(in_state(WorldEditorState::On)),
                 refresh_tilemap.after(save_and_load::spawn_level_on_load)
@@ -72,11 +86,11 @@ pub fn set_mouse_world_coords(
 pub struct WorldEditorUIParent;
 
 pub fn open_world_editor(
-    keys: Res<Input<KeyCode>>,
+    actions: Res<Input<Action>>,
     edit_mode: Res<State<WorldEditorState>>,
     mut next_edit_mode: ResMut<NextState<WorldEditorState>>,
 ) {
-    if keys.just_pressed(KeyCode::T) {
+    if actions.just_pressed(Action::ToggleEditor) {
         if *edit_mode == WorldEditorState::Off {
             next_edit_mode.set(WorldEditorState::On);
             info!("enabling world edit mode.");
@@ -90,9 +104,57 @@ pub fn open_world_editor(
 #[derive(Component, Default)]
 pub struct PlacementDropdown;
 
+/// What clicking in the editor does, picked with the placement dropdown. The dropdown lists
+/// these first, and then every world object that can be placed by clicking.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+enum EditorTool {
+    Tiles,
+    Multiselect,
+    MovePlayer,
+    SnapObjects,
+    CameraZones,
+    LinkObjects,
+    EditPaths,
+    ResizeZones,
+    /// Places the world object with this index in the registry.
+    Place(usize),
+}
+
+const EDITOR_TOOLS: [(EditorTool, &str); 8] = [
+    (EditorTool::Tiles, "World"),
+    (EditorTool::Multiselect, "Multiselect"),
+    (EditorTool::MovePlayer, "Move Player"),
+    (EditorTool::SnapObjects, "Snap and Visualize Objects Movement"),
+    (EditorTool::CameraZones, "Camera Zones"),
+    (EditorTool::LinkObjects, "Link Objects"),
+    (EditorTool::EditPaths, "Edit Platform Paths"),
+    (EditorTool::ResizeZones, "Resize Zones"),
+];
+
+impl EditorTool {
+    fn choices(objects: &[ObjectRegistration]) -> Vec<&'static str> {
+        EDITOR_TOOLS.iter().map(|tool| tool.1)
+            .chain(objects.iter().filter(|object| object.placeable).map(|object| object.name))
+            .collect()
+    }
+
+    fn from_choice(chosen: usize, objects: &[ObjectRegistration]) -> Option<Self> {
+        if let Some(tool) = EDITOR_TOOLS.get(chosen) { return Some(tool.0) }
+
+        objects.iter().enumerate()
+            .filter(|object| object.1.placeable)
+            .nth(chosen - EDITOR_TOOLS.len())
+            .map(|object| EditorTool::Place(object.0))
+    }
+}
+
+#[derive(Component, Default)]
+pub struct TilePaletteDropdown;
+
 const EDITOR_ZOOM: f32 = 2.0;
 pub fn setup_world_editor_gui(
     mut camera: Query<(&mut OrthographicProjection, &mut GameCamera), With<Camera>>,
+    objects: Res<WorldObjects>,
     mut commands: Commands,
 ) {
     let mut camera = camera.single_mut();
@@ -114,14 +176,21 @@ pub fn setup_world_editor_gui(
 
     commands.spawn(DropdownBundle {
         dropdown: Dropdown {
-            choices: vec!["World", "Word Tags", "Lock Zones", "Player Spawner", "Fan", 
-                "Multiselect", "Move Player", "Snap and Visualize Objects Movement", 
-                "Death Zones", "Camera Zones"],
+            choices: EditorTool::choices(&objects.read()),
             chosen: 0,
         },
         marker: PlacementDropdown,
         ..default()
     }).set_parent(ui_parent);
+
+    commands.spawn(DropdownBundle {
+        dropdown: Dropdown {
+            choices: tiles::TILE_PALETTE.iter().map(|tile| tile.1).collect(),
+            chosen: 0,
+        },
+        marker: TilePaletteDropdown,
+        ..default()
+    }).set_parent(ui_parent);
 }
 
 pub fn teardown_world_editor_gui(
@@ -140,11 +209,32 @@ pub fn teardown_world_editor_gui(
 #[derive(SystemParam)]
 pub struct EditorState<'s> {
     multiselect_coords: Local<'s, (Vec2, Vec2)>,
+    camera_zone_start: Local<'s, Option<Vec2>>,
+    /// The object that was clicked first with the link tool, which the next clicked object
+    /// becomes the target of.
+    link_source: Local<'s, Option<Entity>>,
+    /// The moving platform whose path is being edited, and the point being dragged, if any.
+    path_edit: Local<'s, Option<(Entity, Option<usize>)>>,
+}
+
+impl EditorState<'_> {
+    /// The tiles the multiselection covers in the level at `level`, if both of its corners are
+    /// inside that level.
+    fn selection(&self, level: &Transform, tiles: &Grid<TileIndex>) -> Option<TileRect> {
+        let (start, end) = *self.multiselect_coords;
+        if start == end { return None }
+
+        let corner = |position| TileCoord::from_world(position, level)
+            .filter(|coord| coord.in_grid(tiles));
+
+        Some(TileRect::from_corners(corner(start)?, corner(end)?))
+    }
 }
 
 pub fn edit_world(
     mut levels: Query<(&Transform, &mut LoadedLevel, Entity)>,
     placement_dropdown: Query<&Dropdown, With<PlacementDropdown>>,
+    tile_palette: Query<&Dropdown, (With<TilePaletteDropdown>, Without<PlacementDropdown>)>,
     mut player: Query<&mut Transform, (With<Player>, Without<LoadedLevel>)>,
     mut other_objects: Query<(&mut Transform, Option<&Collider>, &GlobalTransform, Entity), 
                              (Without<Player>, Without<LoadedLevel>)>,
@@ -152,21 +242,28 @@ pub fn edit_world(
     parent: Query<&Parent>,
     tile_query: Query<Entity, Or<(With<TilePos>, With<WorldCollider>)>>,
     mouse_button: Res<Input<MouseButton>>,
-    keys: Res<Input<KeyCode>>,
+    actions: Res<Input<Action>>,
+    ids: Query<(&ObjectId, &GlobalTransform, Entity)>,
+    mut platforms: Query<&mut MovingPlatform>,
     mouse_world_coords: Res<MouseWorldCoords>,
     assets: Res<MiscAssets>,
+    objects: Res<WorldObjects>,
     mut gizmos: Gizmos,
     mut commands: Commands,
     mut editor: EditorState,
 ) {
-    let placement_dropdown = placement_dropdown.single();
+    let Some(tool) = EditorTool::from_choice(placement_dropdown.single().chosen, &objects.read())
+        else { return };
+    let palette_tile = tiles::TILE_PALETTE[tile_palette.single().chosen].0;
 
     for mut level in &mut levels {
-        use KeyCode as KC;
+        use Action as A;
         use MouseButton as MB;
 
         let Some(mouse_position) = mouse_world_coords.position else { return };
         let pos_on_map = mouse_position;
+        // objects are children of the level, so they're placed relative to it
+        let pos_in_level = to_level_space(pos_on_map, level.0);
 
         let Some(level_rect) = level_is_in_position((&*level.1, level.0), pos_on_map)
             else { continue };
@@ -185,6 +282,16 @@ pub fn edit_world(
                                Color::RED,
                            );
                        },
+                       ColliderView::ConvexPolygon(polygon) => {
+                           let offset = global.translation().xy();
+                           let points = polygon.points().map(|point| point + offset).collect_vec();
+                           for (from, to) in points.iter().circular_tuple_windows() {
+                               gizmos.line_2d(*from, *to, Color::RED);
+                           }
+                       },
+                       ColliderView::Ball(ball) => {
+                           gizmos.circle_2d(global.translation().xy(), ball.radius(), Color::RED);
+                       },
                        _ => error!("could not visualize: {collider:?}"),
                    }
                } else {
@@ -200,33 +307,34 @@ pub fn edit_world(
         gizmos.rect_2d(level_rect.center(), 0., level_rect.size(), Color::BLUE);
 
         // secondary match to handle indicators based on the dropdown
-        match placement_dropdown.chosen {
-            5 => {
-                gizmos.rect_2d(
-                    (editor.multiselect_coords.0 + editor.multiselect_coords.1) / 2.,
-                    0., 
-                    editor.multiselect_coords.1 - editor.multiselect_coords.0, 
-                    Color::GREEN
-                );
+        match tool {
+            EditorTool::Multiselect => {
+                if let Some(selection) = editor.selection(level.0, &level.1.tiles) {
+                    let rect = selection.world_rect(level.0);
+                    gizmos.rect_2d(rect.center(), 0., rect.size(), Color::GREEN);
+                }
+            },
+            EditorTool::CameraZones if editor.camera_zone_start.is_some() => {
+                let start = editor.camera_zone_start.unwrap();
+                gizmos.rect_2d((start + pos_on_map) / 2., 0., pos_on_map - start, Color::YELLOW);
+            },
+            EditorTool::LinkObjects => {
+                if let Some(source) = *editor.link_source && let Ok(source) = ids.get(source) {
+                    gizmos.line_2d(source.1.translation().xy(), pos_on_map, Color::CYAN);
+                }
             },
             _ => {},
-            }
+        }
 
         // primary match to handle interactions
-        match placement_dropdown.chosen {
-            0 if !mouse_button.get_pressed().is_empty() => { // world
-                let tile_pos = (pos_on_map - level.0.translation.xy() + 8.0) / 16.0;
-                let tile_pos = (tile_pos.x as usize, tile_pos.y as usize);
-
-                let tiles = &mut level.1.tiles;
-
-                if !(0..tiles.cols()).contains(&tile_pos.1) ||
-                   !(0..tiles.rows()).contains(&tile_pos.0) {
-                    continue;
-                }
+        match tool {
+            EditorTool::Tiles if !mouse_button.get_pressed().is_empty() => { // world
+                let Some(coord) = TileCoord::from_world(pos_on_map, level.0)
+                    .filter(|coord| coord.in_grid(&level.1.tiles))
+                    else { continue };
 
                 let tile = if mouse_button.pressed(MouseButton::Left) {
-                        TileIndex::Ground
+                        palette_tile
                     } else {
                         TileIndex::Air
                     };
@@ -249,108 +357,67 @@ pub fn edit_world(
                     commands.entity(entity.3).despawn_recursive();
                 }
 
-                tiles[(tile_pos.0, tile_pos.1)] = tile;
-            },
-            1 if mouse_button.just_pressed(MB::Left) => {
-                commands.spawn(WordTag::bundle(
-                    &WordTagInWorld {
-                        word_id: WordID::Fast,
-                        transform: Transform::from_translation(pos_on_map.extend(0.)),
-                    },
-                    &*assets,
-                )).set_parent(level.2);
-            },
-            2 if mouse_button.just_pressed(MB::Left) => {
-                commands.spawn(LockZone::bundle(
-                    &LockZoneInWorld {
-                        transform: Transform::from_translation(pos_on_map.extend(-2.)),
-                    },
-                    &*assets,
-                )).set_parent(level.2);
+                // only touch the level when the tile actually changes, since any change to it
+                // refreshes the tilemap.
+                if level.1.tiles[coord] != tile {
+                    level.1.set_tile(coord, tile);
+                }
             },
-            3 if mouse_button.just_pressed(MB::Left) => {
-                commands.spawn(PlayerSpawner::bundle(
-                    &PlayerSpawnerInWorld {
-                        transform: Transform::from_translation(pos_on_map.extend(0.)),
-                    },
-                    &*assets,
-                )).set_parent(level.2);
-            }
-            4 if mouse_button.just_pressed(MB::Left) => {
-                commands.spawn(Fan::bundle(
-                    &FanInWorld {
-                        strength: 1.8,
-                        translation: pos_on_map,
-                        rotation: 0.,
-                        scale: Vec2::new(1., 1.),
-                    },
-                    &*assets,
-                )).set_parent(level.2);
-            }
-            5 if mouse_button.just_pressed(MB::Left) => {
+            EditorTool::Multiselect if mouse_button.just_pressed(MB::Left) => {
                 editor.multiselect_coords.0 = pos_on_map;
             },
-            5 if mouse_button.pressed(MB::Left) => {
+            EditorTool::Multiselect if mouse_button.pressed(MB::Left) => {
                 editor.multiselect_coords.1 = pos_on_map;
             },
-            5 if mouse_button.just_released(MB::Left) => {
-                editor.multiselect_coords.0 =
-                     (editor.multiselect_coords.0 / 16.).floor() * 16. + 8.;
-                editor.multiselect_coords.1 =
-                     (editor.multiselect_coords.1 / 16.).floor() * 16. + 8.;
-            },
-            5 if keys.any_just_pressed([KC::Left, KC::Right, KC::Up, KC::Down]) => {
-                let tmove_dir = if keys.just_pressed(KC::Left) { IVec2::new(-1, 0) } 
-                else if keys.just_pressed(KC::Right) { IVec2::new(1, 0) } 
-                else if keys.just_pressed(KC::Up) { IVec2::new(0, 1) } 
-                else if keys.just_pressed(KC::Down) { IVec2::new(0, -1) } 
+            EditorTool::Multiselect if actions.any_just_pressed([
+              A::ShiftSelectionLeft, A::ShiftSelectionRight,
+              A::ShiftSelectionUp, A::ShiftSelectionDown]) => {
+                let move_dir = if actions.just_pressed(A::ShiftSelectionLeft) { IVec2::new(-1, 0) } 
+                else if actions.just_pressed(A::ShiftSelectionRight) { IVec2::new(1, 0) } 
+                else if actions.just_pressed(A::ShiftSelectionUp) { IVec2::new(0, 1) } 
+                else if actions.just_pressed(A::ShiftSelectionDown) { IVec2::new(0, -1) } 
                 else { unreachable!() };
-                
+
+                let Some(selection) = editor.selection(level.0, &level.1.tiles) else { continue };
+
+                // tiles can't be moved off the edge of the level
+                let moved_corners = [selection.min, selection.max - 1]
+                    .map(|corner| TileCoord::new(corner.x as usize, corner.y as usize))
+                    .map(|corner| corner.offset(move_dir));
+                if !moved_corners.iter().all(|corner| {
+                    corner.is_some_and(|corner| corner.in_grid(&level.1.tiles))
+                }) {
+                    continue;
+                }
+
                 let tilemap_copy = level.1.tiles.clone();
-                
-                let tmultiselect_start: IVec2 = 
-                    (editor.multiselect_coords.0 / 16. - level.0.translation.xy()).as_ivec2();
-                let tmultiselect_end: IVec2 = 
-                    (editor.multiselect_coords.1 / 16. - level.0.translation.xy()).as_ivec2();
-
-                // not sure why we have to add one here, but otherwise the movement rect
-                // is off.
-                let tstart = tmultiselect_start.min(tmultiselect_end) + 1;
-                let tend = tmultiselect_start.max(tmultiselect_end) + 1;
-
-                for y in tstart.y..tend.y {
-                    for x in tstart.x..tend.x {
-                        if !(tstart.y..tend.y).contains(&(y - tmove_dir.y)) || 
-                          !(tstart.x..tend.x).contains(&(x - tmove_dir.x)) {
-                            level.1.tiles[(x as usize, y as usize)] = TileIndex::Air;
-                        }
 
-                        level.1.tiles[((x + tmove_dir.x) as usize, (y + tmove_dir.y) as usize)] = 
-                            tilemap_copy[(x as usize, y as usize)];
+                for coord in selection.iter() {
+                    // the tiles the selection moves off of are left empty
+                    if !coord.offset(-move_dir).is_some_and(|from| selection.contains(from)) {
+                        level.1.set_tile(coord, TileIndex::Air);
                     }
                 }
+                for coord in selection.iter() {
+                    level.1.set_tile(coord.offset(move_dir).unwrap(), tilemap_copy[coord]);
+                }
 
-                let wmove_dir = tmove_dir.as_vec2() * 16.;
-                editor.multiselect_coords.0 += wmove_dir;
-                editor.multiselect_coords.1 += wmove_dir;
-
-                let wstart = tstart.as_vec2() * 16.;
-                let wend = tend.as_vec2() * 16.;
+                let world_move_dir = move_dir.as_vec2() * TILE_SIZE;
+                editor.multiselect_coords.0 += world_move_dir;
+                editor.multiselect_coords.1 += world_move_dir;
 
+                let selection_rect = selection.world_rect(level.0);
                 for object in children.iter_descendants(level.2) {
-                    if let Ok(mut object) = other_objects.get_mut(object) {
-                        let pos = &mut object.2.translation();
-                        if wstart.x <= pos.x && pos.x <= wend.x && 
-                          wstart.y <= pos.y && pos.y <= wend.y {
-                            *pos += wmove_dir.extend(0.);
-                        }
+                    if let Ok(mut object) = other_objects.get_mut(object) &&
+                      selection_rect.contains(object.2.translation().xy()) {
+                        object.0.translation += world_move_dir.extend(0.);
                     }
                 }
             },
-            6 if mouse_button.just_pressed(MB::Left) =>  {
+            EditorTool::MovePlayer if mouse_button.just_pressed(MB::Left) =>  {
                 player.single_mut().translation = pos_on_map.extend(0.);
             },
-            7 if mouse_button.just_released(MB::Left) => {
+            EditorTool::SnapObjects if mouse_button.just_released(MB::Left) => {
                 for object in children.iter_descendants(level.2) {
                    if let Ok((mut transformation, ..)) = other_objects.get_mut(object) {
                        transformation.translation = 
@@ -359,66 +426,437 @@ pub fn edit_world(
                    }
                 }
             },
-            8 if mouse_button.just_pressed(MB::Left) => {
-                commands.spawn(DeathZone::bundle(
-                    &DeathZoneInWorld {
-                        transform: Transform::from_translation(pos_on_map.extend(0.)),
+            EditorTool::CameraZones if mouse_button.just_pressed(MB::Left) => {
+                *editor.camera_zone_start = Some(pos_on_map);
+            }
+            EditorTool::CameraZones if mouse_button.just_released(MB::Left) => {
+                let Some(start) = editor.camera_zone_start.take() else { continue };
+                let size = ((pos_on_map - start).abs() / TILE_SIZE).round();
+                if size.x < 1. || size.y < 1. { continue }
+
+                commands.spawn(CameraZone::bundle(
+                    &CameraZoneInWorld {
+                        transform: Transform::from_translation(
+                            to_level_space((start + pos_on_map) / 2., level.0).extend(0.)
+                        ).with_scale(size.extend(1.)),
+                        zoom: 1.,
+                        follow: CameraFollow::Free,
                     },
                     &*assets,
-                )).set_parent(level.2);
+                )).insert(ObjectId::new()).set_parent(level.2);
             }
+            EditorTool::LinkObjects if mouse_button.just_pressed(MB::Left) => {
+                let Some(clicked) = object_at(pos_on_map, &ids) else { continue };
+
+                let Some(source) = editor.link_source.take() else {
+                    *editor.link_source = Some(clicked.2);
+                    continue;
+                };
+                if source == clicked.2 { continue }
+
+                let (objects, target) = ((*objects).clone(), *clicked.0);
+                commands.add(move |world: &mut World| {
+                    if !objects.set_target(world, source, Some(target)) {
+                        warn!("{source:?} can't point at other objects");
+                    }
+                });
+            }
+            EditorTool::EditPaths if mouse_button.just_pressed(MB::Left) => {
+                let clicked = path_point_at(pos_in_level, level.2, &children, &platforms);
+
+                if let Some((platform, point)) = clicked {
+                    *editor.path_edit = Some((platform, Some(point)));
+                } else if let Some((platform, _)) = *editor.path_edit &&
+                  let Ok(mut path) = platforms.get_mut(platform) {
+                    // clicking off the path adds a point to the end of it
+                    path.path.push(pos_in_level);
+                    *editor.path_edit = Some((platform, Some(path.path.len() - 1)));
+                }
+            },
+            EditorTool::EditPaths if mouse_button.pressed(MB::Left) => {
+                let Some((platform, Some(point))) = *editor.path_edit else { continue };
+                let Ok(mut platform) = platforms.get_mut(platform) else { continue };
+
+                platform.path[point] = pos_in_level;
+            },
+            EditorTool::EditPaths if mouse_button.just_released(MB::Left) => {
+                if let Some((_, point)) = &mut *editor.path_edit {
+                    *point = None;
+                }
+            },
+            EditorTool::EditPaths if mouse_button.just_pressed(MB::Right) => {
+                let Some((platform, point)) =
+                    path_point_at(pos_in_level, level.2, &children, &platforms) else { continue };
+                let mut platform = platforms.get_mut(platform).unwrap();
+
+                // a path needs at least two points to go between
+                if platform.path.len() > 2 {
+                    platform.path.remove(point);
+                    platform.path_changed();
+                }
+            },
+            EditorTool::LinkObjects if mouse_button.just_pressed(MB::Right) => {
+                let Some(clicked) = object_at(pos_on_map, &ids) else { continue };
+                *editor.link_source = None;
+
+                let (objects, source) = ((*objects).clone(), clicked.2);
+                commands.add(move |world: &mut World| {
+                    objects.set_target(world, source, None);
+                });
+            }
+            EditorTool::Place(object) if mouse_button.just_pressed(MB::Left) => {
+                let objects = objects.read();
+                if let Some(placed) = objects[object].place(pos_in_level, &*assets, &mut commands) {
+                    commands.entity(placed).set_parent(level.2);
+                }
+            },
             _ => {},
         }
     }
 }
 
+/// The object closest to `position`, if any are close enough to click on.
+fn object_at<'a>(
+    position: Vec2,
+    ids: &'a Query<(&ObjectId, &GlobalTransform, Entity)>,
+) -> Option<(&'a ObjectId, &'a GlobalTransform, Entity)> {
+    const PICK_DISTANCE: f32 = 12.;
+
+    ids.iter()
+        .map(|object| (object, object.1.translation().xy().distance(position)))
+        .filter(|(_, distance)| *distance <= PICK_DISTANCE)
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(object, _)| object)
+}
+
+/// The moving platform in `level` with a path point near `position`, and which point it is.
+/// Both are in level space.
+fn path_point_at(
+    position: Vec2,
+    level: Entity,
+    children: &Query<&Children>,
+    platforms: &Query<&mut MovingPlatform>,
+) -> Option<(Entity, usize)> {
+    const PICK_DISTANCE: f32 = 6.;
+
+    children.iter_descendants(level)
+        .filter_map(|child| Some((child, platforms.get(child).ok()?)))
+        .flat_map(|(child, platform)| {
+            platform.path.iter().enumerate().map(move |(index, point)| {
+                ((child, index), point.distance(position))
+            })
+        })
+        .filter(|(_, distance)| *distance <= PICK_DISTANCE)
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(point, _)| point)
+}
+
+pub fn draw_platform_paths(
+    platforms: Query<(&MovingPlatform, &Parent)>,
+    levels: Query<&GlobalTransform>,
+    mut gizmos: Gizmos,
+) {
+    for (platform, level) in &platforms {
+        let Ok(level) = levels.get(level.get()) else { continue };
+        let offset = level.translation().xy();
+        let points = platform.path.iter().map(|point| *point + offset).collect_vec();
+
+        for (from, to) in points.iter().tuple_windows() {
+            gizmos.line_2d(*from, *to, Color::ORANGE);
+        }
+        if platform.mode == PathMode::Loop && let (Some(first), Some(last)) =
+          (points.first(), points.last()) {
+            gizmos.line_2d(*last, *first, Color::ORANGE);
+        }
+        for point in points {
+            gizmos.circle_2d(point, 3., Color::ORANGE);
+        }
+    }
+}
+
+/// Finds every object that points at another one, and where both of them are. Links to
+/// signal sources are colored by whether the source is on.
+pub fn find_object_links(ecs: &World, objects: Res<WorldObjects>) -> Vec<(Vec2, Vec2, Color)> {
+    let objects = objects.read();
+    let targets: HashMap<ObjectId, (Vec2, Color)> = ecs.iter_entities()
+        .filter_map(|entity| {
+            let color = match entity.get::<SignalSource>() {
+                Some(source) if source.on => Color::GREEN,
+                Some(_) => Color::DARK_GREEN,
+                None => Color::CYAN,
+            };
+            Some((*entity.get::<ObjectId>()?, (position_of(entity)?, color)))
+        })
+        .collect();
+
+    ecs.iter_entities()
+        .filter_map(|entity| {
+            let target = objects.iter().find_map(|registration| registration.target(entity))?;
+            let (to, color) = *targets.get(&target)?;
+            Some((position_of(entity)?, to, color))
+        })
+        .collect()
+}
+
+fn position_of(entity: EntityRef) -> Option<Vec2> {
+    Some(entity.get::<GlobalTransform>()?.translation().xy())
+}
+
+pub fn draw_object_links(In(links): In<Vec<(Vec2, Vec2, Color)>>, mut gizmos: Gizmos) {
+    for (from, to, color) in links {
+        gizmos.line_2d(from, to, color);
+        gizmos.circle_2d(to, 4., color);
+    }
+}
+
+pub fn draw_force_zones(
+    zones: Query<(&ForceZone, &GlobalTransform)>,
+    mut gizmos: Gizmos,
+) {
+    for (zone, transform) in &zones {
+        let (center, half_size) = zone.world_bounds(transform);
+        let color = if zone.on { Color::TEAL } else { Color::GRAY };
+
+        // rectangular zones are outlined by draw_zone_areas
+        if zone.shape == ZoneShape::Circle {
+            gizmos.circle_2d(center, half_size.min_element(), color);
+        }
+
+        // an arrow the way the zone pushes
+        let direction = zone.force.direction.normalize_or_zero();
+        let tip = center + direction * half_size.min_element();
+        gizmos.line_2d(center, tip, color);
+        for side in [-1., 1.] {
+            let barb = Vec2::from_angle(side * 2.5).rotate(direction) * 4.;
+            gizmos.line_2d(tip, tip + barb, color);
+        }
+    }
+}
+
+/// A part of a zone's outline that can be dragged with the resize tool.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+enum ZoneHandle {
+    Corner(usize),
+    /// The middle of the edge after a polygon's corner, which adds a corner when dragged.
+    Edge(usize),
+}
+
+/// Where the handles of a zone are in world space.
+fn zone_handles(area: &ZoneArea, transform: &GlobalTransform) -> Vec<(ZoneHandle, Vec2)> {
+    let corners = area.corners();
+    let to_world = |point: Vec2| transform.transform_point(point.extend(0.)).xy();
+
+    let edges = match area {
+        ZoneArea::Rect { .. } => Vec::new(),
+        ZoneArea::Polygon { .. } => corners.iter()
+            .circular_tuple_windows()
+            .enumerate()
+            .map(|(index, (from, to))| (ZoneHandle::Edge(index), to_world((*from + *to) / 2.)))
+            .collect(),
+    };
+
+    corners.iter().enumerate()
+        .map(|(index, corner)| (ZoneHandle::Corner(index), to_world(*corner)))
+        .chain(edges)
+        .collect()
+}
+
+/// The zone with a handle near `position`, and which handle it is.
+fn zone_handle_at<T: Zone>(
+    position: Vec2,
+    zones: &Query<(&mut T, &mut Transform, &GlobalTransform, Entity)>,
+) -> Option<(Entity, ZoneHandle)> {
+    const PICK_DISTANCE: f32 = 6.;
+
+    zones.iter()
+        .flat_map(|zone| {
+            zone_handles(zone.0.area(), zone.2).into_iter()
+                .map(move |(handle, at)| ((zone.3, handle), at.distance(position)))
+        })
+        .filter(|(_, distance)| *distance <= PICK_DISTANCE)
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(handle, _)| handle)
+}
+
+/// Resizes zones with the resize tool. Dragging a corner moves it, and dragging the middle
+/// of a polygon's edge adds a corner there. Right clicking a rectangle's corner turns it into
+/// a polygon, and right clicking a polygon's corner takes it out.
+pub fn edit_zone_areas<T: Zone>(
+    mut zones: Query<(&mut T, &mut Transform, &GlobalTransform, Entity)>,
+    placement_dropdown: Query<&Dropdown, With<PlacementDropdown>>,
+    mouse_button: Res<Input<MouseButton>>,
+    mouse_world_coords: Res<MouseWorldCoords>,
+    objects: Res<WorldObjects>,
+    mut dragging: Local<Option<(Entity, usize)>>,
+) {
+    let tool = EditorTool::from_choice(placement_dropdown.single().chosen, &objects.read());
+    if tool != Some(EditorTool::ResizeZones) { return }
+    let Some(mouse_position) = mouse_world_coords.position else { return };
+
+    if mouse_button.just_pressed(MouseButton::Left) {
+        *dragging = zone_handle_at(mouse_position, &zones).map(|(entity, handle)| {
+            match handle {
+                ZoneHandle::Corner(index) => (entity, index),
+                ZoneHandle::Edge(index) => {
+                    zones.get_mut(entity).unwrap().0.area_mut().add_corner(index);
+                    (entity, index + 1)
+                },
+            }
+        });
+    } else if mouse_button.pressed(MouseButton::Left) {
+        let Some((entity, index)) = *dragging else { return };
+        let Ok((mut zone, mut transform, global, _)) = zones.get_mut(entity) else { return };
+
+        let to = global.affine().inverse().transform_point3(mouse_position.extend(0.)).xy();
+        if zone.area().corners().get(index) == Some(&to) { return }
+
+        let moved = zone.area_mut().drag_corner(index, to);
+        transform.translation += transform.rotation * moved.extend(0.);
+
+        // rectangles dragged past their opposite corner are flipped, so the dragged corner
+        // is a different one now
+        let to = to - moved;
+        let nearest = zone.area().corners().iter()
+            .position_min_by(|a, b| a.distance(to).total_cmp(&b.distance(to)));
+        *dragging = nearest.map(|index| (entity, index));
+    } else {
+        *dragging = None;
+    }
+
+    if mouse_button.just_pressed(MouseButton::Right) &&
+      let Some((entity, ZoneHandle::Corner(index))) = zone_handle_at(mouse_position, &zones) {
+        let mut zone = zones.get_mut(entity).unwrap().0;
+        if matches!(zone.area(), ZoneArea::Rect { .. }) {
+            zone.area_mut().add_corner(index);
+        } else {
+            zone.area_mut().remove_corner(index);
+        }
+    }
+}
+
+pub fn draw_zone_areas<T: Zone>(
+    zones: Query<(&T, &GlobalTransform)>,
+    placement_dropdown: Query<&Dropdown, With<PlacementDropdown>>,
+    objects: Res<WorldObjects>,
+    mut gizmos: Gizmos,
+) {
+    let tool = EditorTool::from_choice(placement_dropdown.single().chosen, &objects.read());
+
+    for (zone, transform) in &zones {
+        let corners = zone.area().corners().into_iter()
+            .map(|corner| transform.transform_point(corner.extend(0.)).xy())
+            .collect_vec();
+        for (from, to) in corners.iter().circular_tuple_windows() {
+            gizmos.line_2d(*from, *to, Color::FUCHSIA);
+        }
+
+        if tool != Some(EditorTool::ResizeZones) { continue }
+        for (handle, at) in zone_handles(zone.area(), transform) {
+            let radius = match handle { ZoneHandle::Corner(_) => 3., ZoneHandle::Edge(_) => 1.5 };
+            gizmos.circle_2d(at, radius, Color::FUCHSIA);
+        }
+    }
+}
+
+pub fn draw_camera_zones(
+    zones: Query<&GlobalTransform, With<CameraZone>>,
+    mut gizmos: Gizmos,
+) {
+    for zone in &zones {
+        let rect = CameraZone::rect(zone);
+        gizmos.rect_2d(rect.center(), 0., rect.size(), Color::YELLOW);
+    }
+}
+
 // this system triggers both on changes from the editor and from changes on asset loading.
 // i don't really like how these aren't explicitly hooked up, and i think it would be
 // a better idea to make this a callable function in the future.
-fn refresh_tilemap(
+pub fn refresh_tilemap(
     mut worlds: Query<
-        (&LoadedLevel, &mut TileStorage, &mut TilemapSize, Option<&Children>, Entity), 
-        Changed<LoadedLevel>
+        (&mut LoadedLevel, &mut TileStorage, &mut TilemapSize, Option<&Children>, Entity), 
+        (Changed<LoadedLevel>, Without<streaming::Unloaded>)
     >,
     asset_events: EventReader<AssetEvent<DeLevel>>,
     tiles: Query<(), Or<(With<TilePos>, With<WorldCollider>)>>,
+    world_colliders: Query<&TileRect, With<WorldCollider>>,
     mut commands: Commands,
 ) {
-    for mut world in &mut worlds {
-        *world.2 = TilemapSize { x: world.0.tiles.cols() as u32, y: world.0.tiles.rows() as u32};
-        *world.1 = TileStorage::empty(*world.2);
-
-        if asset_events.is_empty() && let Some(children) = world.3 {
-            for child in children {
-                if tiles.get(*child).is_ok() {
-                    commands.entity(*child).despawn_recursive();
+    for (mut level, mut storage, mut size, children, entity) in &mut worlds {
+        // taking the dirty region mustn't count as a change, or we'd refresh every frame
+        let dirty = level.bypass_change_detection().dirty.take();
+        let level = &*level;
+
+        let new_size = tilemap_size(&level.tiles);
+        let whole_level = TileRect::of_grid(&level.tiles);
+
+        // the neighbours of a changed tile can change texture too, so they're redone as well
+        let (region, incremental) = match dirty {
+            Some(dirty) if *size == new_size => (dirty.grow(1, whole_level), true),
+            _ => {
+                *size = new_size;
+                *storage = TileStorage::empty(*size);
+
+                if asset_events.is_empty() && let Some(children) = children {
+                    for child in children {
+                        if tiles.get(*child).is_ok() {
+                            commands.entity(*child).despawn_recursive();
+                        }
+                    }
                 }
+
+                (whole_level, false)
             }
-        }
+        };
 
-        for ((x, y), tile) in world.0.tiles.indexed_iter() {
-            let tile_pos = TilePos { x: x as u32, y: y as u32 };
+        for coord in region.iter() {
+            let tile_pos = coord.tile_pos();
 
-            if *tile == TileIndex::Air {
-                // tile is already none, we don't have to set it
-                world.1.remove(&tile_pos);
-            } else {
+            if let Some(old_tile) = storage.get(&tile_pos) {
+                commands.entity(old_tile).despawn_recursive();
+                storage.remove(&tile_pos);
+            }
+
+            if let Some(texture_index) = tiles::autotile(&level.tiles, coord) {
                 let tile_entity = commands
                     .spawn(TileBundle {
                         position: tile_pos,
-                        tilemap_id: TilemapId(world.4),
+                        tilemap_id: TilemapId(entity),
+                        texture_index,
                         ..Default::default()
                     })
-                    .set_parent(world.4)
+                    .set_parent(entity)
                     .id();
 
-                world.1.set(&tile_pos, tile_entity);
+                storage.set(&tile_pos, tile_entity);
             }
         }
 
-        let world_colliders = calculate_world_colliders(&world.0.tiles);
+        // colliders touching the region are merged again together with it. the ones that
+        // aren't keep their tiles, so the new colliders don't overlap them. the region can
+        // grow to cover the whole level, so this goes by which path was taken above.
+        let mut merge_region = region;
+        let mut kept = Vec::new();
+        if incremental {
+            for child in children.into_iter().flatten() {
+                let Ok(collider_tiles) = world_colliders.get(*child) else { continue };
+
+                if collider_tiles.intersects(region) {
+                    merge_region = merge_region.union(*collider_tiles);
+                    commands.entity(*child).despawn_recursive();
+                } else {
+                    kept.push(*collider_tiles);
+                }
+            }
+        }
+
+        let mut covered = Covered::new(merge_region);
+        for collider_tiles in kept {
+            covered.mark(collider_tiles);
+        }
+
+        let world_colliders = calculate_world_colliders(&level.tiles, merge_region, covered);
         for collider in world_colliders {
-            commands.spawn(collider).set_parent(world.4);
+            commands.spawn(collider).set_parent(entity);
         }
     }
 }
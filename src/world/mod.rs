@@ -4,7 +4,7 @@ const LOAD_AT_START: &'static str = "jungle.world.ron";
 
 use std::path::PathBuf;
 
-use crate::{prelude::*, word::WordID};
+use crate::prelude::*;
 use bevy::{asset::{*, io::*}, app::AppExit, window::exit_on_all_closed};
 use bevy_ecs_tilemap::prelude::*;
 use ron::ser::PrettyConfig;
@@ -12,18 +12,26 @@ use ron::ser::PrettyConfig;
 use self::editor::{WorldEditorState, WorldEditorPlugin};
 
 mod editor;
-mod objects;
+pub mod objects;
 mod save_and_load;
 pub mod helpers;
+pub mod death;
+pub mod streaming;
+pub mod tiles;
+pub mod collider_merge;
+pub mod tile_coord;
 
 use objects::*;
+use tiles::TileIndex;
+use collider_merge::{Covered, merge_rects, merge_runs};
+use tile_coord::{TileCoord, TileRect};
 
 pub struct WorldPlugin;
 
 impl Plugin for WorldPlugin {
     fn build(&self, app: &mut App) {
         app
-            .add_systems(Startup, save_and_load::setup_world)
+            .add_systems(Startup, (save_and_load::setup_world, death::setup_death_counter_ui))
             .add_systems(Update, (
                 save_and_load::save_world,
                 save_and_load::spawn_world_on_load.pipe(save_and_load::spawn_level_on_load),
@@ -33,65 +41,140 @@ impl Plugin for WorldPlugin {
                 word_tag::update,
                 lock_zone::update,
                 player_spawner::update,
+                player_spawner::activate_checkpoints,
+                player_spawner::respawn_player,
+                player_spawner::fade_respawn_effects,
                 fan::update.before(SentenceModificationRoutine),
+                word_zone::update.after(fan::update).before(SentenceModificationRoutine),
+                (
+                    pressure_plate::update,
+                    switch::update,
+                    signal::receive_signals::<Door>,
+                    signal::receive_signals::<Fan>,
+                    signal::receive_signals::<LockZone>,
+                    signal::receive_signals::<MovingPlatform>,
+                    signal::receive_signals::<ForceZone>,
+                    signal::receive_signals::<WordZone>,
+                    (door::update, moving_platform::update, force_zone::update, water_zone::update),
+                ).chain().before(fan::update).before(lock_zone::update).before(word_zone::update),
                 death_zone::update,
+                (
+                    zone_area::update_areas::<LockZone>,
+                    zone_area::update_areas::<DeathZone>,
+                    zone_area::update_areas::<Fan>,
+                    zone_area::update_areas::<WaterZone>,
+                    zone_area::update_areas::<ForceZone>,
+                    zone_area::update_areas::<WordZone>,
+                ),
+                pushable_crate::setup_crates.before(SentenceModificationRoutine),
+                pushable_crate::remember_crate_words.after(SentenceModificationRoutine),
+                (level_exit::update, level_exit::place_player_at_entry).chain(),
             ))
+            .add_systems(Update, (
+                update_current_level,
+                death::snapshot_level_start,
+                (death::count_deaths, death::reset_on_death, death::shake_on_death),
+                death::update_death_counter_ui,
+            ).chain())
+            .add_event::<death::PlayerDied>()
+            .init_resource::<CurrentLevel>()
+            .init_resource::<level_exit::PendingTransition>()
+            .init_resource::<death::DeathCounter>()
+            .init_resource::<death::LevelStart>()
+            .init_resource::<streaming::LevelStreaming>()
+            .add_systems(Update, 
+                (streaming::stream_levels, apply_deferred).chain()
+                    .before(editor::refresh_tilemap)
+            )
             .add_plugins(TilemapPlugin)
             .add_plugins(WorldEditorPlugin)
             .init_resource::<editor::MouseWorldCoords>()
+            .init_resource::<player_spawner::Checkpoint>()
+            .init_resource::<player_spawner::PlayerPlaced>()
             .enable_functions::<LoadedLevel>()
             .enable_functions::<LoadedWorld>()
             .init_asset::<DeLevel>()
             .init_asset::<DeWorld>()
-            .register_asset_loader(save_and_load::LevelLoader)
+            .init_resource::<WorldObjects>()
+            .register_world_object::<WordTag>()
+            .register_world_object::<LockZone>()
+            .register_world_object::<PlayerSpawner>()
+            .register_world_object::<Fan>()
+            .register_world_object::<DeathZone>()
+            .register_world_object::<CameraZone>()
+            .register_world_object::<LevelEntry>()
+            .register_world_object::<LevelExit>()
+            .register_world_object::<PressurePlate>()
+            .register_world_object::<Switch>()
+            .register_world_object::<Door>()
+            .register_world_object::<MovingPlatform>()
+            .register_world_object::<Crate>()
+            .register_world_object::<ForceZone>()
+            .register_world_object::<WordZone>()
+            .register_world_object::<WaterZone>();
+
+        let objects = app.world.resource::<WorldObjects>().clone();
+        app
+            .register_asset_loader(save_and_load::LevelLoader { objects })
             .register_asset_loader(save_and_load::WorldLoader);
     }
 }
 
-#[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
-#[repr(u32)]
-pub enum TileIndex {
-    #[serde(rename = "G")]
-    Ground = 0,
-    #[default]
-    #[serde(rename = "A")]
-    Air = u32::MAX,
-}
-
-#[derive(Debug, Resource, Asset, TypePath, Serialize, Deserialize)]
+#[derive(Resource, Asset, TypePath)]
 pub struct DeLevel {
-    #[serde(default = "empty_grid")]
     tiles: Grid<TileIndex>,
-    #[serde(default)] player_spanwers: Vec<PlayerSpawnerInWorld>,
-    #[serde(default)] word_tags: Vec<WordTagInWorld>,
-    #[serde(default)] lock_zones: Vec<LockZoneInWorld>,
-    #[serde(default)] fans: Vec<FanInWorld>,
-    #[serde(default)] death_zones: Vec<DeathZoneInWorld>,
+    /// The list of each kind of world object in the level, by its key. These are
+    /// `Vec<Placed<T::InWorld>>`s, see `registry`.
+    objects: HashMap<&'static str, ObjectList>,
+}
+
+impl DeLevel {
+    /// The objects of one kind in the level.
+    pub fn objects<T: WorldObject>(&self) -> &[Placed<T::InWorld>] {
+        self.objects.get(T::KEY)
+            .and_then(|list| list.downcast_ref::<Vec<Placed<T::InWorld>>>())
+            .map_or(&[][..], |list| list.as_slice())
+    }
 }
 
 impl Default for DeLevel {
     fn default() -> Self {
         Self {
             tiles: Grid::new(100, 100),
-            player_spanwers: default(),
-            word_tags: default(),
-            lock_zones: default(),
-            fans: default(),
-            death_zones: default(),
+            objects: default(),
         }
     }
 }
 
-
-#[derive(Debug, Resource, Asset, TypePath, Serialize, Deserialize, Default)]
-pub struct DeWorld {
-    levels: Vec<(Vec3, PathBuf)>,
-}
-
-#[derive(Component)]
+#[derive(Component, Default)]
 pub struct LoadedLevel {
     pub handle: Handle<DeLevel>,
     pub tiles: Grid<TileIndex>,
+    /// The tiles changed through `set_tile` since the tilemap was last refreshed. If this is
+    /// `None` when the level changes, the whole tilemap is rebuilt.
+    dirty: Option<TileRect>,
+}
+
+impl LoadedLevel {
+    /// Changes one tile, and remembers it so only the area around it has to be rebuilt.
+    pub fn set_tile(&mut self, coord: TileCoord, tile: TileIndex) {
+        if self.tiles[coord] == tile { return }
+
+        self.tiles[coord] = tile;
+        let changed = TileRect::single(coord);
+        self.dirty = Some(self.dirty.map_or(changed, |dirty| dirty.union(changed)));
+    }
+
+    /// Rebuilds the whole tilemap the next time it's refreshed.
+    pub fn rebuild_all(&mut self) {
+        self.dirty = None;
+    }
+
+    /// Replaces all of the tiles, which rebuilds the whole tilemap.
+    pub fn set_tiles(&mut self, tiles: Grid<TileIndex>) {
+        self.tiles = tiles;
+        self.dirty = None;
+    }
 }
 
 impl GrayboxFunctions for LoadedLevel {
@@ -115,14 +198,38 @@ impl GrayboxFunctions for LoadedLevel {
         }
 
         vec![
-            ("add row to bottom", atb),
+            // columns of the grid are stacked upwards, and rows go rightwards. see tile_coord.
+            ("add row to top", atb),
             ("add col to right", atr),
-            ("remove row from bottom", rfb),
-            ("remove col add right", rfr),
+            ("remove row from top", rfb),
+            ("remove col from right", rfr),
         ]
     }
 }
 
+/// The level the player is standing in, or the last one they stood in if they're between
+/// levels.
+#[derive(Resource, Default)]
+pub struct CurrentLevel {
+    pub entity: Option<Entity>,
+}
+
+fn update_current_level(
+    mut current_level: ResMut<CurrentLevel>,
+    player: Query<&Transform, With<Player>>,
+    levels: Query<(&LoadedLevel, &Transform, Entity), Without<Player>>,
+) {
+    let player = player.single().translation.xy();
+
+    let level = levels.iter()
+        .find(|level| helpers::level_is_in_position((level.0, level.1), player).is_some())
+        .map(|level| level.2);
+
+    if level.is_some() && level != current_level.entity {
+        current_level.entity = level;
+    }
+}
+
 #[derive(Default, Component)]
 pub struct LoadedWorld {
     handle: Handle<DeWorld>,
@@ -147,11 +254,18 @@ pub struct LevelQuery {
     storage: &'static mut TileStorage,
     size: &'static mut TilemapSize,
     loaded_level: &'static mut LoadedLevel,
+    memory: &'static mut streaming::LevelMemory,
+    unloaded: Has<streaming::Unloaded>,
     entity: Entity,
 }
 
-#[derive(Component, Default)]
-struct WorldCollider;
+#[derive(Component, Default, Debug, Copy, Clone, PartialEq, Eq)]
+pub enum WorldCollider {
+    #[default]
+    Solid,
+    OneWay,
+    Slope,
+}
 
 #[derive(Default, Bundle)]
 struct WorldCollderBundle {
@@ -160,56 +274,67 @@ struct WorldCollderBundle {
     rigidbody: RigidBody,
     collider: Collider,
     world_collider: WorldCollider,
+    hooks: ActiveHooks,
+    /// The tiles this collider covers.
+    tiles: TileRect,
 }
 
-fn calculate_world_colliders(tiles: &Grid<TileIndex>) -> Vec<WorldCollderBundle> {
-    let mut filled = Grid::<bool>::new(tiles.rows() as usize, tiles.cols() as usize);
-
+/// Merges the tiles in `region` into colliders, leaving alone the tiles in `covered`, which
+/// already have one.
+fn calculate_world_colliders(
+    tiles: &Grid<TileIndex>,
+    region: TileRect,
+    mut covered: Covered,
+) -> Vec<WorldCollderBundle> {
     let mut output = Vec::<WorldCollderBundle>::new();
 
-    use TileIndex::Air as Air;
-
-    for start_x in 0..tiles.rows() {
-        for start_y in 0..tiles.cols() {
-            let mut x = start_x;
-            let mut y = start_y;
-
-            if tiles[(x, y)] == Air || filled[(x, y)] { continue }
-
-            while x < tiles.rows() as usize &&
-                tiles[(x, y)] != Air && 
-                !filled[(x, y)] {
-                x += 1;
-            }
-
-            while y < tiles.cols() &&
-              (start_x..x).all(|x| tiles[(x, y)] != Air) &&
-              (start_x..x).all(|x| !filled[(x, y)]) {
-                y += 1;
-            }
-
-            for fill_x in start_x..x {
-                for fill_y in start_y..y {
-                    filled[(fill_x, fill_y)] = true;
-                }
-            }
-
-            let rect = Rect {
-                min: Vec2::new(start_x as f32, start_y as f32) * 16.0,
-                max: Vec2::new(x as f32, y as f32) * 16.0,
-            };
-
-            output.push(WorldCollderBundle {
-                transform: Transform {
-                    translation: (rect.center() - Vec2::splat(8.)).extend(0.),
-                    ..default()
-                },
-                // we divide by 2 because cuboid takes half sizes
-                collider: Collider::cuboid(rect.size().x / 2., rect.size().y / 2.),
-                rigidbody: RigidBody::Fixed,
-                ..default()
-            })
-        }
+    for rect in merge_rects(region, &mut covered, |coord| tiles[coord].is_full()) {
+        let in_level = rect.level_rect();
+
+        output.push(WorldCollderBundle {
+            transform: Transform::from_translation(in_level.center().extend(0.)),
+            // we divide by 2 because cuboid takes half sizes
+            collider: Collider::cuboid(in_level.half_size().x, in_level.half_size().y),
+            rigidbody: RigidBody::Fixed,
+            tiles: rect,
+            ..default()
+        })
+    }
+
+    // one-way platforms only merge sideways, into thin planks along the top of the tiles.
+    const PLATFORM_THICKNESS: f32 = 4.;
+    let platforms = merge_runs(
+        region, &mut covered, |coord| tiles[coord] == TileIndex::Platform);
+
+    for run in platforms {
+        let in_level = run.level_rect();
+
+        output.push(WorldCollderBundle {
+            transform: Transform::from_xyz(
+                in_level.center().x, in_level.max.y - PLATFORM_THICKNESS / 2., 0.),
+            collider: Collider::cuboid(in_level.half_size().x, PLATFORM_THICKNESS / 2.),
+            rigidbody: RigidBody::Fixed,
+            world_collider: WorldCollider::OneWay,
+            hooks: ActiveHooks::MODIFY_SOLVER_CONTACTS,
+            tiles: run,
+            ..default()
+        });
+    }
+
+    // slopes don't merge with anything, each one gets its own collider.
+    for coord in region.iter() {
+        if covered.is_covered(coord) { continue }
+        let Some(points) = tiles[coord].slope_points() else { continue };
+        let Some(collider) = Collider::convex_hull(points) else { continue };
+
+        output.push(WorldCollderBundle {
+            transform: Transform::from_translation(coord.center().extend(0.)),
+            collider,
+            rigidbody: RigidBody::Fixed,
+            world_collider: WorldCollider::Slope,
+            tiles: TileRect::single(coord),
+            ..default()
+        });
     }
 
     output
@@ -0,0 +1,75 @@
+use crate::prelude::*;
+
+use super::WorldObject;
+
+/// While the player is inside a camera zone, the camera stays inside the zone's rect instead
+/// of the level's, and zooms and follows the player the way the zone says. The zone's size
+/// is its scale, in tiles.
+#[derive(Component)]
+pub struct CameraZone {
+    /// Values above one zoom in, values below one zoom out.
+    pub zoom: f32,
+    pub follow: CameraFollow,
+}
+
+impl Default for CameraZone {
+    fn default() -> Self {
+        Self { zoom: 1., follow: default() }
+    }
+}
+
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CameraFollow {
+    /// The camera sits in the middle of the zone.
+    Locked,
+    /// The camera follows the player left and right, but stays vertically centered.
+    HorizontalOnly,
+    #[default]
+    Free,
+}
+
+impl CameraZone {
+    pub fn rect(transform: &GlobalTransform) -> Rect {
+        let (scale, _, translation) = transform.to_scale_rotation_translation();
+        Rect::from_center_size(translation.xy(), scale.xy() * 16.)
+    }
+}
+
+#[derive(Default, Bundle)]
+pub struct CameraZoneBundle {
+    zone: CameraZone,
+    spatial: SpatialBundle,
+    name: Name,
+}
+
+fn default_zoom() -> f32 { 1. }
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CameraZoneInWorld {
+    pub transform: Transform,
+    #[serde(default = "default_zoom")] pub zoom: f32,
+    #[serde(default)] pub follow: CameraFollow,
+}
+
+impl WorldObject for CameraZone {
+    type Bundle = CameraZoneBundle;
+    type InWorld = CameraZoneInWorld;
+
+    const KEY: &'static str = "camera_zones";
+    const NAME: &'static str = "Camera Zones";
+
+    fn bundle(in_world: &CameraZoneInWorld, _: &MiscAssets) -> Self::Bundle {
+        CameraZoneBundle {
+            zone: CameraZone {
+                zoom: in_world.zoom,
+                follow: in_world.follow,
+            },
+            spatial: SpatialBundle::from_transform(in_world.transform),
+            name: Name::new("Camera Zone"),
+        }
+    }
+
+    fn in_world(&self, transform: &Transform) -> CameraZoneInWorld {
+        CameraZoneInWorld { transform: *transform, zoom: self.zoom, follow: self.follow }
+    }
+}
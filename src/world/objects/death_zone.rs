@@ -1,9 +1,22 @@
-use crate::{prelude::*, word::{SentenceStructure, ui::SentenceSection, movement::Player, spawn::WordObject}};
+use crate::{prelude::*, word::{SentenceStructure, movement::Player, spawn::WordObject}};
 
-use super::{WorldObject, player_spawner::PlayerSpawner};
+use crate::world::{CurrentLevel, death::PlayerDied};
+use super::{WorldObject, Zone, ZoneArea, player_spawner::{Respawning, Spawners}};
 
 #[derive(Default, Component)]
-pub struct DeathZone;
+pub struct DeathZone {
+    pub sentence_reset: SentenceReset,
+    pub area: ZoneArea,
+}
+
+/// What happens to the player's sentence when they die in a death zone.
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SentenceReset {
+    #[default]
+    Keep,
+    /// Go back to the sentence and vocabulary the player had when they entered the level.
+    LevelStart,
+}
 
 #[derive(Default, Bundle)]
 pub struct DeathZoneBundle {
@@ -17,41 +30,89 @@ pub struct DeathZoneBundle {
     name: Name,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct DeathZoneInWorld {
     pub transform: Transform,
+    #[serde(default)] pub sentence_reset: SentenceReset,
+    #[serde(default)] pub area: Option<ZoneArea>,
 }
 
 impl WorldObject for DeathZone {
     type Bundle = DeathZoneBundle;
     type InWorld = DeathZoneInWorld;
 
+    const KEY: &'static str = "death_zones";
+    const NAME: &'static str = "Death Zones";
+
     fn bundle(in_world: &DeathZoneInWorld, _: &MiscAssets) -> Self::Bundle {
+        let (area, transform) = ZoneArea::from_legacy(&in_world.area, &in_world.transform);
+
         DeathZoneBundle {
-            spatial: SpatialBundle::from_transform(in_world.transform),
-            collider: Collider::cuboid(8., 8.),
+            spatial: SpatialBundle::from_transform(transform),
+            collider: area.collider(),
+            word_tag: DeathZone { sentence_reset: in_world.sentence_reset, area },
             rigidbody: RigidBody::Fixed,
             events: ActiveEvents::all(),
             name: Name::new("Death Zone"),
             ..default()
         }
     }
+
+    fn in_world(&self, transform: &Transform) -> DeathZoneInWorld {
+        DeathZoneInWorld {
+            transform: *transform,
+            sentence_reset: self.sentence_reset,
+            area: Some(self.area.clone()),
+        }
+    }
+
+    fn placed_at(position: Vec2) -> Option<DeathZoneInWorld> {
+        Some(DeathZoneInWorld {
+            transform: Transform::from_translation(position.extend(0.)),
+            area: Some(ZoneArea::default()),
+            ..default()
+        })
+    }
+}
+
+impl Zone for DeathZone {
+    fn area(&self) -> &ZoneArea { &self.area }
+    fn area_mut(&mut self) -> &mut ZoneArea { &mut self.area }
 }
 
 pub fn update(
-    zones: Query<&CollidingEntities, (Changed<CollidingEntities>, With<DeathZone>)>,
-    mut word_objects_and_player: ParamSet<(
-        Query<&mut Transform, With<WordObject>>,
-        Query<&mut Transform, With<Player>>,
-    )>,
-    spawners: Query<&Transform, (With<PlayerSpawner>, Without<WordObject>, Without<Player>)>,
+    zones: Query<(&DeathZone, &CollidingEntities), Changed<CollidingEntities>>,
+    word_objects: Query<&WordObject>,
+    mut player: Query<(&Transform, &mut Velocity, &mut Visibility, Entity),
+        (With<Player>, Without<Respawning>)>,
+    spawners: Spawners,
+    current_level: Res<CurrentLevel>,
+    mut deaths: EventWriter<PlayerDied>,
+    mut commands: Commands,
 ) {
-    for zone in &zones {
-        for colliding in zone.iter() {
-            if word_objects_and_player.p0().get_mut(colliding).is_ok() {
-                let spawner = spawners.iter().next().unwrap();
-                *word_objects_and_player.p1().single_mut() = *spawner;
-            }
-        }
-    }
+    let Ok(mut player) = player.get_single_mut() else { return };
+
+    // only the player dies, other things with words like crates just fall through
+    let Some(zone) = zones.iter().find(|zone| {
+        zone.1.iter().any(|colliding| {
+            word_objects.get(colliding).is_ok_and(|word_object| word_object.sentence == player.3)
+        })
+    }) else { return };
+
+    let Some((spawner, at)) = spawners.respawn_point(player.0.translation.xy()) else {
+        warn!("player died, but there's no spawner to respawn them at");
+        return;
+    };
+
+    *player.1 = Velocity::zero();
+    *player.2 = Visibility::Hidden;
+    commands.entity(player.3).insert((
+        Respawning::at_spawner(spawner, at),
+        RigidBodyDisabled::default(),
+    ));
+
+    deaths.send(PlayerDied {
+        level: current_level.entity,
+        sentence_reset: zone.0.sentence_reset,
+    });
 }
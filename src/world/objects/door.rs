@@ -0,0 +1,85 @@
+use crate::prelude::*;
+
+use super::{WorldObject, ObjectId, SignalReceiver};
+
+/// A solid block that opens while the source it targets is on.
+#[derive(Default, Component)]
+pub struct Door {
+    pub signal: Option<ObjectId>,
+    pub open: bool,
+}
+
+#[derive(Default, Bundle)]
+pub struct DoorBundle {
+    door: Door,
+    sprite: SpriteBundle,
+    collider: Collider,
+    rigidbody: RigidBody,
+    name: Name,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DoorInWorld {
+    pub transform: Transform,
+    #[serde(default)] pub signal: Option<ObjectId>,
+}
+
+impl WorldObject for Door {
+    type Bundle = DoorBundle;
+    type InWorld = DoorInWorld;
+
+    const KEY: &'static str = "doors";
+    const NAME: &'static str = "Doors";
+
+    fn bundle(in_world: &DoorInWorld, assets: &MiscAssets) -> Self::Bundle {
+        DoorBundle {
+            door: Door { signal: in_world.signal, open: false },
+            sprite: SpriteBundle {
+                transform: in_world.transform,
+                texture: assets.square_pale.clone(),
+                ..default()
+            },
+            collider: Collider::cuboid(8., 8.),
+            rigidbody: RigidBody::Fixed,
+            name: Name::new("Door"),
+        }
+    }
+
+    fn in_world(&self, transform: &Transform) -> DoorInWorld {
+        DoorInWorld { transform: *transform, signal: self.signal }
+    }
+
+    fn placed_at(position: Vec2) -> Option<DoorInWorld> {
+        Some(DoorInWorld {
+            // two tiles tall, standing on the tile that was clicked
+            transform: Transform::from_translation(Vec3::new(position.x, position.y + 8., -1.))
+                .with_scale(Vec3::new(1., 2., 1.)),
+            signal: None,
+        })
+    }
+
+    const HAS_TARGET: bool = true;
+
+    fn target(&self) -> Option<ObjectId> { self.signal }
+
+    fn set_target(&mut self, target: Option<ObjectId>) { self.signal = target }
+}
+
+impl SignalReceiver for Door {
+    fn powered(&self) -> bool { self.open }
+    fn set_powered(&mut self, powered: bool) { self.open = powered }
+}
+
+pub fn update(
+    doors: Query<(&Door, Entity), Changed<Door>>,
+    mut commands: Commands,
+) {
+    for (door, entity) in &doors {
+        let mut door_entity = commands.entity(entity);
+        if door.open {
+            door_entity.insert((ColliderDisabled, Visibility::Hidden));
+        } else {
+            door_entity.remove::<ColliderDisabled>().insert(Visibility::Inherited);
+        }
+    }
+}
@@ -1,10 +1,14 @@
 use crate::{prelude::*, word::{*, apply_words::QWordObject}};
 
-use super::WorldObject;
+use super::{WorldObject, ObjectId, SignalReceiver, Zone, ZoneArea};
 
 #[derive(Default, Component)]
 pub struct Fan {
     pub strength: f32,
+    /// The source that turns the fan on and off. Fans without one are always on.
+    pub signal: Option<ObjectId>,
+    pub on: bool,
+    pub area: ZoneArea,
 }
 
 #[derive(Default, Bundle)]
@@ -25,39 +29,90 @@ pub struct FanInWorld {
     #[serde(default)] pub translation: Vec2,
     #[serde(default)] pub scale: Vec2,
     #[serde(default)] pub rotation: f32,
+    #[serde(default)] pub signal: Option<ObjectId>,
+    /// Older fans were sized by `scale` instead.
+    #[serde(default)] pub area: Option<ZoneArea>,
 }
 
 impl WorldObject for Fan {
     type Bundle = FanBundle;
     type InWorld = FanInWorld;
 
+    const KEY: &'static str = "fans";
+    const NAME: &'static str = "Fan";
+
     fn bundle(in_world: &FanInWorld, assets: &MiscAssets) -> Self::Bundle {
+        let (area, transform) = ZoneArea::from_legacy(&in_world.area, &{
+            let mut transform = Transform {
+                translation: Vec3 {
+                    x: in_world.translation.x,
+                    y: in_world.translation.y,
+                    z: -2.0,
+                },
+                scale: in_world.scale.extend(1.),
+                ..default()
+            };
+            transform.rotate_z(in_world.rotation);
+            transform
+        });
+
         FanBundle {
-            fan: Fan { strength: in_world.strength },
             sprite: SpriteBundle { 
-                transform: {
-                    let mut transform = Transform {
-                        translation: Vec3 {
-                            x: in_world.translation.x,
-                            y: in_world.translation.y,
-                            z: -2.0,
-                        },
-                        scale: in_world.scale.extend(1.),
-                        ..default()
-                    };
-                    transform.rotate_z(in_world.rotation);
-                    transform
-                },
+                transform,
                 texture: assets.square_pink.clone(),
+                sprite: Sprite {
+                    custom_size: Some(area.bounds().size()),
+                    anchor: area.sprite_anchor(),
+                    ..default()
+                },
                 ..default() 
             },
-            collider: Collider::cuboid(8., 8.),
+            collider: area.collider(),
+            fan: Fan { strength: in_world.strength, signal: in_world.signal, on: true, area },
             rigidbody: RigidBody::Fixed,
             events: ActiveEvents::all(),
             name: Name::new("Fan"),
             ..default()
         }
     }
+
+    fn in_world(&self, transform: &Transform) -> FanInWorld {
+        FanInWorld {
+            strength: self.strength,
+            translation: transform.translation.xy(),
+            rotation: transform.rotation.to_euler(EulerRot::XYZ).2,
+            scale: transform.scale.xy(),
+            signal: self.signal,
+            area: Some(self.area.clone()),
+        }
+    }
+
+    fn placed_at(position: Vec2) -> Option<FanInWorld> {
+        Some(FanInWorld {
+            strength: 1.8,
+            translation: position,
+            rotation: 0.,
+            scale: Vec2::new(1., 1.),
+            signal: None,
+            area: Some(ZoneArea::default()),
+        })
+    }
+
+    const HAS_TARGET: bool = true;
+
+    fn target(&self) -> Option<ObjectId> { self.signal }
+
+    fn set_target(&mut self, target: Option<ObjectId>) { self.signal = target }
+}
+
+impl Zone for Fan {
+    fn area(&self) -> &ZoneArea { &self.area }
+    fn area_mut(&mut self) -> &mut ZoneArea { &mut self.area }
+}
+
+impl SignalReceiver for Fan {
+    fn powered(&self) -> bool { self.on }
+    fn set_powered(&mut self, powered: bool) { self.on = powered }
 }
 
 pub fn update(
@@ -73,7 +128,8 @@ pub fn update(
         .collect();
     let mut all_colliding = HashSet::<Entity>::new();
 
-    for fan in &fans {
+    // objects in a fan that's off stop fluttering, like they left it
+    for fan in fans.iter().filter(|fan| fan.0.on) {
         for colliding in fan.1.iter() {
             all_colliding.insert(colliding);
             if currently_fluttering.contains(&colliding) { continue; }
@@ -82,22 +138,17 @@ pub fn update(
                 if let Ok(mut sentence) = sentences.get_mut(ancestor) {
                     if !sentence.0.valid { continue }
 
-                    let root = sentence.0.root;
-                    let adjective_id = id_of_adjective(&mut *sentence.0, root,
-                        &split_adjectives).unwrap();
-
                     let dir = match fan.2.rotation.z {
                         z if z > -0.35 && z < 0.35 => WordID::FlutteringUp,
                         z if z < -0.35 && z > -1.05 => WordID::FlutteringRight,
                         _ => WordID::FlutteringUp,
                     };
 
-                    sentence.0.sentence[adjective_id].word = Some(dir);
-                    sentence.0.sentence[adjective_id].locked = true;
-
-                    structure_changes.send(SentenceStructureChanged {
-                        on: sentence.1,
-                    });
+                    if sentence.0.inject_adjective(dir) {
+                        structure_changes.send(SentenceStructureChanged {
+                            on: sentence.1,
+                        });
+                    }
 
                     break;
                 }
@@ -111,41 +162,15 @@ pub fn update(
                 if let Ok(mut sentence) = sentences.get_mut(ancestor) {
                     if !sentence.0.valid { continue }
 
-                    let root = sentence.0.root;
-
-                    id_of_adjective(
-                        &mut *sentence.0, root,
-                        &|id, sentence| {
-                            if let PhraseKind::Combine { l, r } = 
-                                sentence.sentence[id].kind {
-                                let search = [
-                                    Some(WordID::FlutteringUp), 
-                                    Some(WordID::FlutteringRight)
-                                ];
-                                let word = if search.contains(&sentence.sentence[l].word) {
-                                    sentence.sentence[r].word
-                                } else if search.contains(&sentence.sentence[r].word) {
-                                    sentence.sentence[l].word
-                                } else {
-                                    return None;
-                                };
-
-                                sentence.sentence[id] = PhraseData {
-                                    word,
-                                    kind: PhraseKind::Adjective,
-                                    locked: false,
-                                };
-
-                                Some(id)
-                            } else {
-                                None
-                            }
-                        }
-                    ).unwrap();
-
-                    structure_changes.send(SentenceStructureChanged {
-                        on: sentence.1,
-                    });
+                    let removed_up = sentence.0.remove_injected_adjective(WordID::FlutteringUp);
+                    let removed_right =
+                        sentence.0.remove_injected_adjective(WordID::FlutteringRight);
+
+                    if removed_up || removed_right {
+                        structure_changes.send(SentenceStructureChanged {
+                            on: sentence.1,
+                        });
+                    }
 
                     break;
                 }
@@ -153,45 +178,3 @@ pub fn update(
         }
     }
 }
-
-fn id_of_adjective(
-    sentence: &mut SentenceStructure, 
-    id: PhraseID, 
-    filter: &impl Fn(PhraseID, &mut SentenceStructure) -> Option<PhraseID>,
-) -> Option<PhraseID> {
-    if let Some(id) = filter(id, sentence) {
-        return Some(id);
-    }
-
-    match sentence.sentence[id] {
-        PhraseData { kind: PhraseKind::Noun { adjective }, .. } => {
-            id_of_adjective(sentence, adjective, filter)
-        }
-        PhraseData { kind: PhraseKind::Combine { l, r }, .. } => {
-            id_of_adjective(sentence, l, filter)
-                .or_else(|| id_of_adjective(sentence, r, filter))
-        }
-        _ => None
-    }
-}
-
-fn split_adjectives(phrase_id: PhraseID, sentence: &mut SentenceStructure) -> Option<PhraseID> {
-    let phrase_data = sentence.sentence[phrase_id];
-    if phrase_data.kind == PhraseKind::Adjective {
-        let l = sentence.sentence.insert(
-            PhraseData { kind: PhraseKind::Adjective, ..default() });
-        let r = sentence.sentence.insert(PhraseData {
-            word: phrase_data.word,
-            kind: PhraseKind::Adjective,
-            ..default()
-        });
-        sentence.sentence[phrase_id] = PhraseData {
-            word: Some(WordID::And),
-            kind: PhraseKind::Combine { l, r },
-            locked: true,
-        };
-        Some(l)
-    } else {
-        None
-    }
-}
@@ -0,0 +1,223 @@
+use crate::prelude::*;
+
+use super::{WorldObject, ObjectId, SignalReceiver, Zone, ZoneArea};
+
+/// A zone that pushes the bodies in it, like wind tunnels, conveyors and water currents. It
+/// works on the bodies directly through their `ExternalForce`, and doesn't touch their
+/// sentences.
+#[derive(Default, Component)]
+pub struct ForceZone {
+    pub force: ZoneForce,
+    pub shape: ZoneShape,
+    /// The source that turns the zone on and off. Zones without one are always on.
+    pub signal: Option<ObjectId>,
+    pub on: bool,
+    pub area: ZoneArea,
+}
+
+#[derive(Debug, Default, Copy, Clone, Serialize, Deserialize)]
+pub struct ZoneForce {
+    /// Which way the zone pushes, in world space. Doesn't have to be normalized.
+    pub direction: Vec2,
+    /// How fast the zone accelerates things, in pixels per second squared. It's scaled by
+    /// each body's mass, so light and heavy things are pushed the same.
+    pub magnitude: f32,
+    #[serde(default)] pub falloff: Falloff,
+}
+
+/// How the push gets weaker across the zone. Rectangular zones fall off along their
+/// direction, from the edge of their area the force comes from. Circular ones fall off from
+/// the center.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Falloff {
+    #[default]
+    None,
+    Linear,
+    Quadratic,
+}
+
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ZoneShape {
+    /// Covers the zone's area.
+    #[default]
+    Rect,
+    /// The biggest circle that fits in the rectangle around the zone's area.
+    Circle,
+}
+
+#[derive(Default, Bundle)]
+pub struct ForceZoneBundle {
+    zone: ForceZone,
+    spatial: SpatialBundle,
+    collider: Collider,
+    colliding: CollidingEntities,
+    rigidbody: RigidBody,
+    events: ActiveEvents,
+    sensor: Sensor,
+    name: Name,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ForceZoneInWorld {
+    pub transform: Transform,
+    pub force: ZoneForce,
+    #[serde(default)] pub shape: ZoneShape,
+    #[serde(default)] pub signal: Option<ObjectId>,
+    /// Older zones were sized by the scale of `transform` instead.
+    #[serde(default)] pub area: Option<ZoneArea>,
+}
+
+impl WorldObject for ForceZone {
+    type Bundle = ForceZoneBundle;
+    type InWorld = ForceZoneInWorld;
+
+    const KEY: &'static str = "force_zones";
+    const NAME: &'static str = "Force Zones";
+
+    fn bundle(in_world: &ForceZoneInWorld, _: &MiscAssets) -> Self::Bundle {
+        let (area, transform) = ZoneArea::from_legacy(&in_world.area, &in_world.transform);
+        let zone = ForceZone {
+            force: in_world.force,
+            shape: in_world.shape,
+            signal: in_world.signal,
+            on: true,
+            area,
+        };
+
+        ForceZoneBundle {
+            spatial: SpatialBundle::from_transform(transform),
+            collider: zone.collider(),
+            zone,
+            rigidbody: RigidBody::Fixed,
+            events: ActiveEvents::all(),
+            name: Name::new("Force Zone"),
+            ..default()
+        }
+    }
+
+    fn in_world(&self, transform: &Transform) -> ForceZoneInWorld {
+        ForceZoneInWorld {
+            transform: *transform,
+            force: self.force,
+            shape: self.shape,
+            signal: self.signal,
+            area: Some(self.area.clone()),
+        }
+    }
+
+    fn placed_at(position: Vec2) -> Option<ForceZoneInWorld> {
+        Some(ForceZoneInWorld {
+            transform: Transform::from_translation(position.extend(0.)),
+            force: ZoneForce { direction: Vec2::Y, magnitude: 600., falloff: Falloff::Linear },
+            shape: ZoneShape::Rect,
+            signal: None,
+            area: Some(ZoneArea::Rect { size: Vec2::new(32., 64.) }),
+        })
+    }
+
+    const HAS_TARGET: bool = true;
+
+    fn target(&self) -> Option<ObjectId> { self.signal }
+
+    fn set_target(&mut self, target: Option<ObjectId>) { self.signal = target }
+}
+
+impl Zone for ForceZone {
+    fn area(&self) -> &ZoneArea { &self.area }
+    fn area_mut(&mut self) -> &mut ZoneArea { &mut self.area }
+
+    fn collider(&self) -> Collider {
+        match self.shape {
+            ZoneShape::Rect => self.area.collider(),
+            ZoneShape::Circle => {
+                let bounds = self.area.bounds();
+                let ball = Collider::ball(bounds.half_size().min_element());
+                Collider::compound(vec![(bounds.center(), 0., ball)])
+            },
+        }
+    }
+}
+
+impl SignalReceiver for ForceZone {
+    fn powered(&self) -> bool { self.on }
+    fn set_powered(&mut self, powered: bool) { self.on = powered }
+}
+
+impl ForceZone {
+    /// The center and half size of the rectangle around the zone's area, in world space.
+    /// Zones aren't meant to be rotated.
+    pub fn world_bounds(&self, transform: &GlobalTransform) -> (Vec2, Vec2) {
+        let bounds = self.area.bounds();
+        (transform.transform_point(bounds.center().extend(0.)).xy(), bounds.half_size())
+    }
+
+    /// The acceleration the zone gives something at `position`.
+    pub fn acceleration_at(&self, transform: &GlobalTransform, position: Vec2) -> Vec2 {
+        let direction = self.force.direction.normalize_or_zero();
+        let (center, half_size) = self.world_bounds(transform);
+
+        // how far through the zone `position` is, from 0 to 1
+        let through = match self.shape {
+            ZoneShape::Rect => {
+                // how far the zone goes from its center in `direction`
+                let reach = direction.abs().dot(half_size);
+                if reach <= 0. { return Vec2::ZERO }
+
+                ((position - center).dot(direction) + reach) / (reach * 2.)
+            },
+            ZoneShape::Circle => (position - center).length() / half_size.min_element(),
+        }.clamp(0., 1.);
+
+        let strength = match self.force.falloff {
+            Falloff::None => 1.,
+            Falloff::Linear => 1. - through,
+            Falloff::Quadratic => (1. - through).powi(2),
+        };
+
+        direction * self.force.magnitude * strength
+    }
+}
+
+/// Pushes the bodies that have colliders in force zones. Each body is pushed once per zone,
+/// from where its first collider in the zone is. What was added last frame is taken back
+/// out first, so this adds to the forces other systems apply instead of replacing them.
+pub fn update(
+    zones: Query<(&ForceZone, &GlobalTransform, &CollidingEntities)>,
+    colliders: Query<&GlobalTransform>,
+    mut bodies: Query<(&mut ExternalForce, Option<&ReadMassProperties>), With<RigidBody>>,
+    parents: Query<&Parent>,
+    mut applied: Local<HashMap<Entity, Vec2>>,
+) {
+    let mut forces = HashMap::<Entity, Vec2>::new();
+
+    for (zone, zone_transform, colliding) in zones.iter().filter(|zone| zone.0.on) {
+        let mut pushed = HashSet::<Entity>::new();
+
+        for collider in colliding.iter() {
+            let Some(body) = std::iter::once(collider)
+                .chain(parents.iter_ancestors(collider))
+                .find(|entity| bodies.contains(*entity)) else { continue };
+            if !pushed.insert(body) { continue }
+
+            let Ok(position) = colliders.get(collider) else { continue };
+            let acceleration = zone.acceleration_at(zone_transform, position.translation().xy());
+            let mass = bodies.get(body).ok()
+                .and_then(|body| body.1)
+                .map_or(1., |mass| mass.mass);
+
+            *forces.entry(body).or_default() += acceleration * mass;
+        }
+    }
+
+    for (body, old_force) in applied.drain() {
+        if let Ok(mut body) = bodies.get_mut(body) {
+            body.0.force -= old_force;
+        }
+    }
+    for (body, force) in &forces {
+        if let Ok(mut body) = bodies.get_mut(*body) {
+            body.0.force += *force;
+        }
+    }
+    *applied = forces;
+}
@@ -0,0 +1,61 @@
+use crate::prelude::*;
+
+use super::WorldObject;
+
+/// A named point in a level that exits can send the player to.
+#[derive(Component, Default)]
+pub struct LevelEntry {
+    pub name: String,
+}
+
+#[derive(Bundle, Default)]
+pub struct LevelEntryBundle {
+    entry: LevelEntry,
+    transform: Transform,
+    global_transform: GlobalTransform,
+    name: Name,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct LevelEntryInWorld {
+    pub name: String,
+    pub transform: Transform,
+}
+
+impl WorldObject for LevelEntry {
+    type Bundle = LevelEntryBundle;
+    type InWorld = LevelEntryInWorld;
+
+    const KEY: &'static str = "level_entries";
+    const NAME: &'static str = "Level Entries";
+
+    fn bundle(in_world: &LevelEntryInWorld, _: &MiscAssets) -> Self::Bundle {
+        LevelEntryBundle {
+            entry: LevelEntry { name: in_world.name.clone() },
+            transform: in_world.transform,
+            name: Name::new(format!("Entry \"{}\"", in_world.name)),
+            ..default()
+        }
+    }
+
+    fn in_world(&self, transform: &Transform) -> LevelEntryInWorld {
+        LevelEntryInWorld { name: self.name.clone(), transform: *transform }
+    }
+
+    fn placed_at(position: Vec2) -> Option<LevelEntryInWorld> {
+        // named after the tile it's on, so new entries don't share a name
+        let tile = (position / 16.).floor().as_ivec2();
+        Some(LevelEntryInWorld {
+            name: format!("entry_{}_{}", tile.x, tile.y),
+            transform: Transform::from_translation(position.extend(0.)),
+        })
+    }
+}
+
+/// Warns about entry names used more than once in `names`, since exits that go by name only
+/// ever find one of them.
+pub fn warn_duplicate_names<'a>(names: impl IntoIterator<Item = &'a str>) {
+    for name in names.into_iter().duplicates() {
+        warn!("more than one level entry is named \"{name}\"");
+    }
+}
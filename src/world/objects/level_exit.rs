@@ -0,0 +1,175 @@
+use std::path::PathBuf;
+
+use crate::{prelude::*, word::SentenceStructure};
+use crate::world::{LoadedWorld, save_and_load::spawn_world};
+
+use super::{WorldObject, LevelEntry, ObjectId};
+
+/// A zone that sends the player to an entry, either in the current world or in another world
+/// file.
+#[derive(Component, Default)]
+pub struct LevelExit {
+    pub target: ExitTarget,
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct ExitTarget {
+    /// The world file the entry is in, or `None` for the world the exit is in.
+    #[serde(default)] pub world: Option<PathBuf>,
+    /// The entry the exit leads to. Picked in the editor.
+    #[serde(default)] pub entry_id: Option<ObjectId>,
+    /// The name of the entry, which is used instead if `entry_id` isn't set.
+    #[serde(default)] pub entry: String,
+}
+
+impl ExitTarget {
+    pub fn leads_to(&self, id: ObjectId, name: &str) -> bool {
+        match self.entry_id {
+            Some(entry_id) => entry_id == id,
+            None => self.entry == name,
+        }
+    }
+}
+
+#[derive(Default, Bundle)]
+pub struct LevelExitBundle {
+    exit: LevelExit,
+    spatial: SpatialBundle,
+    collider: Collider,
+    colliding: CollidingEntities,
+    rigidbody: RigidBody,
+    events: ActiveEvents,
+    sensor: Sensor,
+    name: Name,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct LevelExitInWorld {
+    pub transform: Transform,
+    pub target: ExitTarget,
+}
+
+impl WorldObject for LevelExit {
+    type Bundle = LevelExitBundle;
+    type InWorld = LevelExitInWorld;
+
+    const KEY: &'static str = "level_exits";
+    const NAME: &'static str = "Level Exits";
+
+    fn bundle(in_world: &LevelExitInWorld, _: &MiscAssets) -> Self::Bundle {
+        LevelExitBundle {
+            exit: LevelExit { target: in_world.target.clone() },
+            spatial: SpatialBundle::from_transform(in_world.transform),
+            collider: Collider::cuboid(8., 8.),
+            rigidbody: RigidBody::Fixed,
+            events: ActiveEvents::all(),
+            name: Name::new(format!("Exit to \"{}\"", in_world.target.entry)),
+            ..default()
+        }
+    }
+
+    fn in_world(&self, transform: &Transform) -> LevelExitInWorld {
+        LevelExitInWorld { transform: *transform, target: self.target.clone() }
+    }
+
+    fn placed_at(position: Vec2) -> Option<LevelExitInWorld> {
+        Some(LevelExitInWorld {
+            transform: Transform::from_translation(position.extend(0.)),
+            // leads nowhere until an entry is picked in the editor
+            target: ExitTarget::default(),
+        })
+    }
+
+    const HAS_TARGET: bool = true;
+
+    fn target(&self) -> Option<ObjectId> {
+        self.target.entry_id
+    }
+
+    fn set_target(&mut self, target: Option<ObjectId>) {
+        self.target.entry_id = target;
+    }
+}
+
+/// Set when the player goes through an exit, until they've been placed at the entry it leads
+/// to. The entry might not be spawned yet if its world is still loading.
+#[derive(Resource, Default)]
+pub struct PendingTransition {
+    pub target: Option<ExitTarget>,
+    give_up: Timer,
+}
+
+const TRANSITION_TIMEOUT: f32 = 10.0;
+
+pub fn update(
+    exits: Query<(&LevelExit, &CollidingEntities), Changed<CollidingEntities>>,
+    parents: Query<&Parent>,
+    player: Query<Entity, With<Player>>,
+    worlds: Query<(&LoadedWorld, Entity)>,
+    asset_server: Res<AssetServer>,
+    mut pending: ResMut<PendingTransition>,
+    mut commands: Commands,
+) {
+    if pending.target.is_some() { return }
+
+    let player = player.single();
+
+    for exit in &exits {
+        let touched_by_player = exit.1.iter().any(|colliding| {
+            parents.iter_ancestors(colliding).any(|ancestor| ancestor == player)
+        });
+        if !touched_by_player { continue }
+
+        let target = &exit.0.target;
+        if target.entry_id.is_none() && target.entry.is_empty() { continue }
+
+        if let Some(world_path) = &target.world {
+            let already_loaded = worlds.iter().any(|world| {
+                asset_server.get_path(world.0.handle.id())
+                    .is_some_and(|path| path.path() == world_path)
+            });
+
+            if !already_loaded {
+                info!("loading world {world_path:?}");
+
+                for world in &worlds {
+                    commands.entity(world.1).despawn_recursive();
+                }
+
+                spawn_world(asset_server.load(world_path.clone()), &mut commands);
+            }
+        }
+
+        pending.target = Some(target.clone());
+        pending.give_up = Timer::from_seconds(TRANSITION_TIMEOUT, TimerMode::Once);
+        commands.entity(player).insert(RigidBodyDisabled::default());
+        break;
+    }
+}
+
+pub fn place_player_at_entry(
+    mut pending: ResMut<PendingTransition>,
+    entries: Query<(&LevelEntry, &ObjectId, &GlobalTransform)>,
+    mut player: Query<(&mut Transform, &mut Velocity, &SentenceStructure, Entity), With<Player>>,
+    time: Res<Time>,
+    mut commands: Commands,
+) {
+    let Some(target) = pending.target.clone() else { return };
+    let (mut transform, mut velocity, sentence, player_entity) = player.single_mut();
+
+    let entry = entries.iter().find(|entry| target.leads_to(*entry.1, &entry.0.name));
+
+    if let Some((_, _, entry_transform)) = entry {
+        transform.translation = entry_transform.translation();
+        *velocity = Velocity::zero();
+    } else if pending.give_up.tick(time.delta()).finished() {
+        warn!("couldn't find the level entry for {target:?}");
+    } else {
+        return;
+    }
+
+    pending.target = None;
+    if sentence.valid {
+        commands.entity(player_entity).remove::<RigidBodyDisabled>();
+    }
+}
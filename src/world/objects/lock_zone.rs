@@ -1,9 +1,14 @@
-use crate::{prelude::*, word::{SentenceStructure, ui::SentenceSection, movement::Player, spawn::WordObject}};
+use crate::{prelude::*, word::{SentenceStructure, spawn::WordObject}};
 
-use super::WorldObject;
+use super::{WorldObject, ObjectId, SignalReceiver, Zone, ZoneArea};
 
 #[derive(Default, Component)]
-pub struct LockZone;
+pub struct LockZone {
+    /// The source that enables and disables the zone. Zones without one are always enabled.
+    pub signal: Option<ObjectId>,
+    pub enabled: bool,
+    pub area: ZoneArea,
+}
 
 #[derive(Default, Bundle)]
 pub struct LockZoneBundle {
@@ -20,49 +25,93 @@ pub struct LockZoneBundle {
 #[derive(Debug, Serialize, Deserialize)]
 pub struct LockZoneInWorld {
     pub transform: Transform,
+    #[serde(default)] pub signal: Option<ObjectId>,
+    #[serde(default)] pub area: Option<ZoneArea>,
 }
 
 impl WorldObject for LockZone {
     type Bundle = LockZoneBundle;
     type InWorld = LockZoneInWorld;
 
+    const KEY: &'static str = "lock_zones";
+    const NAME: &'static str = "Lock Zones";
+
     fn bundle(in_world: &LockZoneInWorld, assets: &MiscAssets) -> Self::Bundle {
+        let (area, transform) = ZoneArea::from_legacy(&in_world.area, &in_world.transform);
+
         LockZoneBundle {
             sprite: SpriteBundle { 
-                transform: in_world.transform,
+                transform,
                 texture: assets.square_yellow.clone(),
+                sprite: Sprite {
+                    custom_size: Some(area.bounds().size()),
+                    anchor: area.sprite_anchor(),
+                    ..default()
+                },
                 ..default() 
             },
-            collider: Collider::cuboid(8., 8.),
+            collider: area.collider(),
+            word_tag: LockZone { signal: in_world.signal, enabled: true, area },
             rigidbody: RigidBody::Fixed,
             events: ActiveEvents::all(),
             name: Name::new("Lock Zone"),
             ..default()
         }
     }
+
+    fn in_world(&self, transform: &Transform) -> LockZoneInWorld {
+        LockZoneInWorld {
+            transform: *transform,
+            signal: self.signal,
+            area: Some(self.area.clone()),
+        }
+    }
+
+    fn placed_at(position: Vec2) -> Option<LockZoneInWorld> {
+        Some(LockZoneInWorld {
+            transform: Transform::from_translation(position.extend(-2.)),
+            signal: None,
+            area: Some(ZoneArea::default()),
+        })
+    }
+
+    const HAS_TARGET: bool = true;
+
+    fn target(&self) -> Option<ObjectId> { self.signal }
+
+    fn set_target(&mut self, target: Option<ObjectId>) { self.signal = target }
+}
+
+impl Zone for LockZone {
+    fn area(&self) -> &ZoneArea { &self.area }
+    fn area_mut(&mut self) -> &mut ZoneArea { &mut self.area }
 }
 
+impl SignalReceiver for LockZone {
+    fn powered(&self) -> bool { self.enabled }
+    fn set_powered(&mut self, powered: bool) { self.enabled = powered }
+}
+
+/// Locks the sentences of word objects in enabled zones, and unlocks the rest.
 pub fn update(
-    zone_changes: Query<(), (Changed<CollidingEntities>, With<LockZone>)>,
+    zone_changes: Query<(), (Or<(Changed<CollidingEntities>, Changed<LockZone>)>, With<LockZone>)>,
     zones: Query<(&LockZone, &CollidingEntities)>,
     word_objects: Query<&WordObject>,
-    mut sentence_sections: Query<&mut SentenceSection>,
+    mut sentences: Query<(&mut SentenceStructure, Entity)>,
 ) {
     if zone_changes.is_empty() { return };
 
-    for mut section in &mut sentence_sections {
-        section.locked = false;
-    }
-
-    for zone in &zones {
-        for colliding_entity in zone.1.iter() {
-            let Ok(word_object) = word_objects.get(colliding_entity) else { continue };
+    let locked: HashSet<Entity> = zones.iter()
+        .filter(|zone| zone.0.enabled)
+        .flat_map(|zone| zone.1.iter())
+        .filter_map(|colliding_entity| word_objects.get(colliding_entity).ok())
+        .map(|word_object| word_object.sentence)
+        .collect();
 
-            for mut section in &mut sentence_sections {
-                if section.sentence_entity == word_object.sentence {
-                    section.locked = true;
-                }
-            }
+    for (mut sentence, entity) in &mut sentences {
+        let lock = locked.contains(&entity);
+        if sentence.is_locked() != lock {
+            sentence.set_locked(lock);
         }
     }
 }
@@ -5,15 +5,70 @@ pub mod lock_zone;
 pub mod player_spawner;
 pub mod word_tag;
 pub mod death_zone;
+pub mod level_entry;
+pub mod level_exit;
+pub mod camera_zone;
+pub mod registry;
+pub mod object_id;
+pub mod signal;
+pub mod pressure_plate;
+pub mod switch;
+pub mod door;
+pub mod moving_platform;
+pub mod pushable_crate;
+pub mod force_zone;
+pub mod word_zone;
+pub mod zone_area;
+pub mod water_zone;
 pub use word_tag::*;
 pub use lock_zone::*;
 pub use player_spawner::*;
 pub use fan::*;
 pub use death_zone::*;
+pub use level_entry::*;
+pub use level_exit::*;
+pub use camera_zone::*;
+pub use registry::*;
+pub use object_id::*;
+pub use signal::*;
+pub use pressure_plate::*;
+pub use switch::*;
+pub use door::*;
+pub use moving_platform::*;
+pub use pushable_crate::*;
+pub use force_zone::*;
+pub use word_zone::*;
+pub use zone_area::*;
+pub use water_zone::*;
 
-pub trait WorldObject: Component {
+/// Something that can be placed in a level, and saved in and loaded from level files. Has to
+/// be registered with `register_world_object`.
+pub trait WorldObject: Component + Sized {
     type Bundle: Bundle;
-    type InWorld;
+    /// What's saved in level files for each object.
+    type InWorld: Serialize + serde::de::DeserializeOwned + Send + Sync + 'static;
+
+    /// The name of the list of these objects in level files.
+    const KEY: &'static str;
+    /// Names the list had in older level files, which are still read.
+    const ALIASES: &'static [&'static str] = &[];
+    /// Shown in the editor.
+    const NAME: &'static str;
 
     fn bundle(in_world: &Self::InWorld, assets: &MiscAssets) -> Self::Bundle;
+
+    /// What gets saved for a spawned object, with `transform` relative to its level.
+    fn in_world(&self, transform: &Transform) -> Self::InWorld;
+
+    /// What the editor places when clicking at `position`, in level space. Objects that
+    /// return `None` can't be placed by clicking.
+    fn placed_at(_position: Vec2) -> Option<Self::InWorld> { None }
+
+    /// Whether the object points at another one, which the editor lets you pick.
+    const HAS_TARGET: bool = false;
+
+    /// The object this one points at, if it has one.
+    fn target(&self) -> Option<ObjectId> { None }
+
+    fn set_target(&mut self, _target: Option<ObjectId>) {}
 }
@@ -0,0 +1,237 @@
+use crate::prelude::*;
+
+use super::{WorldObject, ObjectId, SignalReceiver};
+
+/// A solid block that moves along a path of points. It's a kinematic body that's moved by
+/// setting its velocity, so the player's movement can tell how fast it's going and ride it.
+#[derive(Default, Component)]
+pub struct MovingPlatform {
+    /// The points the platform moves between, in level space. It starts on the first one.
+    pub path: Vec<Vec2>,
+    pub mode: PathMode,
+    /// In pixels per second.
+    pub speed: f32,
+    /// The source that starts and stops the platform. Platforms without one are always
+    /// moving, except for triggered ones, which then never move.
+    pub signal: Option<ObjectId>,
+    on: bool,
+    /// The index of the point the platform is moving towards.
+    to: usize,
+    /// Whether `to` is counting up.
+    forward: bool,
+}
+
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PathMode {
+    /// Goes back to the first point after the last one.
+    Loop,
+    /// Turns around at both ends.
+    #[default]
+    PingPong,
+    /// Moves towards the last point while its signal is on, and back to the first while it's
+    /// off.
+    Triggered,
+}
+
+#[derive(Default, Bundle)]
+pub struct MovingPlatformBundle {
+    platform: MovingPlatform,
+    sprite: SpriteBundle,
+    collider: Collider,
+    rigidbody: RigidBody,
+    velocity: Velocity,
+    name: Name,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MovingPlatformInWorld {
+    pub path: Vec<Vec2>,
+    #[serde(default)] pub mode: PathMode,
+    pub speed: f32,
+    /// The size of the platform in tiles.
+    pub size: Vec2,
+    #[serde(default)] pub signal: Option<ObjectId>,
+}
+
+impl WorldObject for MovingPlatform {
+    type Bundle = MovingPlatformBundle;
+    type InWorld = MovingPlatformInWorld;
+
+    const KEY: &'static str = "moving_platforms";
+    const NAME: &'static str = "Moving Platforms";
+
+    fn bundle(in_world: &MovingPlatformInWorld, assets: &MiscAssets) -> Self::Bundle {
+        let start = in_world.path.first().copied().unwrap_or_default();
+        // triggered platforms wait on their first point until their signal turns on
+        let triggered = in_world.mode == PathMode::Triggered;
+
+        MovingPlatformBundle {
+            platform: MovingPlatform {
+                path: in_world.path.clone(),
+                mode: in_world.mode,
+                speed: in_world.speed,
+                signal: in_world.signal,
+                on: !triggered,
+                to: if triggered { 0 } else { 1.min(in_world.path.len().saturating_sub(1)) },
+                forward: !triggered,
+            },
+            sprite: SpriteBundle {
+                transform: Transform::from_translation(start.extend(-1.))
+                    .with_scale(in_world.size.extend(1.)),
+                texture: assets.square_pale.clone(),
+                sprite: Sprite { color: Color::rgb(0.6, 0.6, 0.7), ..default() },
+                ..default()
+            },
+            collider: Collider::cuboid(8., 8.),
+            rigidbody: RigidBody::KinematicVelocityBased,
+            name: Name::new("Moving Platform"),
+            ..default()
+        }
+    }
+
+    fn in_world(&self, transform: &Transform) -> MovingPlatformInWorld {
+        MovingPlatformInWorld {
+            path: self.path.clone(),
+            mode: self.mode,
+            speed: self.speed,
+            size: transform.scale.xy(),
+            signal: self.signal,
+        }
+    }
+
+    fn placed_at(position: Vec2) -> Option<MovingPlatformInWorld> {
+        Some(MovingPlatformInWorld {
+            path: vec![position, position + Vec2::new(48., 0.)],
+            mode: PathMode::PingPong,
+            speed: 32.,
+            size: Vec2::new(3., 1.),
+            signal: None,
+        })
+    }
+
+    const HAS_TARGET: bool = true;
+
+    fn target(&self) -> Option<ObjectId> { self.signal }
+
+    fn set_target(&mut self, target: Option<ObjectId>) { self.signal = target }
+}
+
+impl SignalReceiver for MovingPlatform {
+    fn powered(&self) -> bool { self.on }
+
+    fn set_powered(&mut self, powered: bool) {
+        self.on = powered;
+
+        // triggered platforms turn around right away, even between two points
+        if self.mode == PathMode::Triggered && self.forward != powered && self.path.len() >= 2 {
+            self.forward = powered;
+            self.to = self.step(self.to);
+        }
+    }
+}
+
+impl MovingPlatform {
+    /// The point the platform is moving towards, or `None` if it's standing still.
+    pub fn next_point(&self) -> Option<Vec2> {
+        if self.path.len() < 2 { return None }
+        if !self.on && self.mode != PathMode::Triggered { return None }
+
+        Some(self.path[self.to])
+    }
+
+    /// The index after `index` in the direction the platform is going, without going past
+    /// either end.
+    fn step(&self, index: usize) -> usize {
+        if self.forward {
+            (index + 1).min(self.path.len() - 1)
+        } else {
+            index.saturating_sub(1)
+        }
+    }
+
+    /// Picks the next point once the platform has reached the one it was moving towards.
+    fn advance(&mut self) {
+        let last = self.path.len() - 1;
+
+        match self.mode {
+            PathMode::Loop => self.to = (self.to + 1) % self.path.len(),
+            PathMode::PingPong => {
+                if self.to == last { self.forward = false }
+                if self.to == 0 { self.forward = true }
+                self.to = self.step(self.to);
+            },
+            PathMode::Triggered => self.to = self.step(self.to),
+        }
+    }
+
+    /// Keeps the platform's place in its path valid after the path was edited.
+    pub fn path_changed(&mut self) {
+        self.to = self.to.min(self.path.len().saturating_sub(1));
+    }
+}
+
+pub fn update(
+    mut platforms: Query<(&mut MovingPlatform, &Transform, &mut Velocity)>,
+    time: Res<Time>,
+) {
+    let delta = time.delta_seconds();
+    if delta == 0. { return }
+
+    for (mut platform, transform, mut velocity) in &mut platforms {
+        let Some(next_point) = platform.next_point() else {
+            velocity.linvel = Vec2::ZERO;
+            continue;
+        };
+
+        let to_next = next_point - transform.translation.xy();
+
+        if to_next.length() <= platform.speed * delta {
+            // land right on the point, and head for the next one from there
+            velocity.linvel = to_next / delta;
+            platform.advance();
+        } else {
+            velocity.linvel = to_next.normalize() * platform.speed;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn triggered_platform() -> MovingPlatform {
+        let in_world = MovingPlatformInWorld {
+            path: vec![Vec2::ZERO, Vec2::new(48., 0.), Vec2::new(48., 48.)],
+            mode: PathMode::Triggered,
+            speed: 32.,
+            size: Vec2::ONE,
+            signal: None,
+        };
+        MovingPlatform::bundle(&in_world, &MiscAssets::default()).platform
+    }
+
+    #[test]
+    fn unpowered_triggered_platforms_stay_at_the_start() {
+        let mut platform = triggered_platform();
+
+        for _ in 0..4 {
+            assert_eq!(platform.next_point(), Some(Vec2::ZERO));
+            platform.advance();
+        }
+    }
+
+    #[test]
+    fn triggered_platforms_go_to_the_end_while_powered_and_back_when_not() {
+        let mut platform = triggered_platform();
+
+        platform.set_powered(true);
+        assert_eq!(platform.next_point(), Some(Vec2::new(48., 0.)));
+        platform.advance();
+        assert_eq!(platform.next_point(), Some(Vec2::new(48., 48.)));
+        platform.advance();
+        assert_eq!(platform.next_point(), Some(Vec2::new(48., 48.)));
+
+        platform.set_powered(false);
+        assert_eq!(platform.next_point(), Some(Vec2::new(48., 0.)));
+    }
+}
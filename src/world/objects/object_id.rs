@@ -0,0 +1,41 @@
+// Every object in a level has an ID that's saved with it, so objects can point at each other
+// in level files, like an exit at the entry it leads to. The IDs stay the same through
+// saving, loading and streaming, unlike an object's position in its list.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::prelude::*;
+
+#[derive(Component, Copy, Clone, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ObjectId(pub u64);
+
+impl ObjectId {
+    /// A new ID. These are scrambled from the time and a counter, so IDs made in different
+    /// sessions or for different levels won't run into each other.
+    pub fn new() -> Self {
+        static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+        let time = SystemTime::now().duration_since(UNIX_EPOCH)
+            .map_or(0, |time| time.as_nanos() as u64);
+        let count = COUNTER.fetch_add(1, Ordering::Relaxed);
+
+        Self(splitmix64(time ^ count.rotate_left(32)))
+    }
+}
+
+/// Scrambles the bits of `x`. Different inputs always give different outputs.
+fn splitmix64(x: u64) -> u64 {
+    let mut x = x.wrapping_add(0x9e3779b97f4a7c15);
+    x = (x ^ (x >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94d049bb133111eb);
+    x ^ (x >> 31)
+}
+
+/// One object in a level file, with the ID other objects refer to it by.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Placed<T> {
+    pub id: ObjectId,
+    pub object: T,
+}
@@ -1,45 +1,260 @@
-use crate::{prelude::*, word::movement::Player};
+use crate::{prelude::*, word::{movement::Player, SentenceStructure, spawn::WordObject}};
+use crate::world::{DeLevel, LoadedLevel, helpers::level_is_in_position};
 
 use super::WorldObject;
 
 #[derive(Component, Default)]
-pub struct PlayerSpawner;
+pub struct PlayerSpawner {
+    pub respawn_delay: f32,
+    pub respawn_effect: bool,
+}
 
 #[derive(Bundle, Default)]
 pub struct PlayerSpawnerBundle {
     spawner: PlayerSpawner,
     transform: Transform,
+    global_transform: GlobalTransform,
+    collider: Collider,
+    colliding: CollidingEntities,
+    rigidbody: RigidBody,
+    events: ActiveEvents,
+    sensor: Sensor,
     name: Name,
 }
 
 #[derive(Component, Default, Debug, Serialize, Deserialize)]
 pub struct PlayerSpawnerInWorld {
     pub transform: Transform,
+    #[serde(default)] pub respawn_delay: f32,
+    #[serde(default)] pub respawn_effect: bool,
 }
 
 impl WorldObject for PlayerSpawner {
     type Bundle = PlayerSpawnerBundle;
     type InWorld = PlayerSpawnerInWorld;
 
+    const KEY: &'static str = "player_spawners";
+    const ALIASES: &'static [&'static str] = &["player_spanwers"];
+    const NAME: &'static str = "Player Spawner";
+
     fn bundle(in_world: &PlayerSpawnerInWorld, _: &MiscAssets) -> Self::Bundle {
         PlayerSpawnerBundle {
+            spawner: PlayerSpawner {
+                respawn_delay: in_world.respawn_delay,
+                respawn_effect: in_world.respawn_effect,
+            },
             transform: in_world.transform,
+            collider: Collider::cuboid(8., 8.),
+            rigidbody: RigidBody::Fixed,
+            events: ActiveEvents::all(),
             name: Name::new("spawner"),
             ..default()
         }
     }
+
+    fn in_world(&self, transform: &Transform) -> PlayerSpawnerInWorld {
+        PlayerSpawnerInWorld {
+            transform: *transform,
+            respawn_delay: self.respawn_delay,
+            respawn_effect: self.respawn_effect,
+        }
+    }
+
+    fn placed_at(position: Vec2) -> Option<PlayerSpawnerInWorld> {
+        Some(PlayerSpawnerInWorld {
+            transform: Transform::from_translation(position.extend(0.)),
+            ..default()
+        })
+    }
+}
+
+/// The spawner the player touched last.
+#[derive(Resource, Default)]
+pub struct Checkpoint {
+    pub spawner: Option<Entity>,
+}
+
+/// Whether the player has been put at their starting point yet. Worlds without a spawner
+/// leave the player where they are, once all their levels have loaded.
+#[derive(Resource, Default)]
+pub struct PlayerPlaced {
+    pub placed: bool,
+}
+
+/// Added to the player between dying and reappearing at a spawner.
+#[derive(Component)]
+pub struct Respawning {
+    pub timer: Timer,
+    pub at: Vec3,
+    pub effect: bool,
+}
+
+impl Respawning {
+    pub fn at_spawner(spawner: &PlayerSpawner, at: Vec3) -> Self {
+        Respawning {
+            timer: Timer::from_seconds(spawner.respawn_delay.max(0.), TimerMode::Once),
+            at,
+            effect: spawner.respawn_effect,
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct RespawnEffect {
+    timer: Timer,
+}
+
+#[derive(SystemParam)]
+pub struct Spawners<'w, 's> {
+    spawners: Query<'w, 's,
+        (&'static PlayerSpawner, &'static GlobalTransform, &'static Parent, Entity)>,
+    levels: Query<'w, 's, (&'static LoadedLevel, &'static Transform, Entity), Without<Player>>,
+    checkpoint: Res<'w, Checkpoint>,
+}
+
+impl<'w, 's> Spawners<'w, 's> {
+    /// Picks the spawner the player should come back at after dying at `position`: the
+    /// active checkpoint if it's in the level the player is in, otherwise the nearest
+    /// spawner in that level, otherwise the nearest spawner anywhere.
+    pub fn respawn_point(&self, position: Vec2) -> Option<(&PlayerSpawner, Vec3)> {
+        let current_level = self.levels.iter()
+            .find(|level| level_is_in_position((level.0, level.1), position).is_some())
+            .map(|level| level.2);
+
+        if let Some(checkpoint) = self.checkpoint.spawner &&
+          let Ok(spawner) = self.spawners.get(checkpoint) &&
+          Some(spawner.2.get()) == current_level {
+            return Some((spawner.0, spawner.1.translation()));
+        }
+
+        let nearest = |in_level: Option<Entity>| {
+            self.spawners.iter()
+                .filter(|spawner| in_level.is_none() || Some(spawner.2.get()) == in_level)
+                .min_by(|a, b| {
+                    let a = a.1.translation().xy().distance_squared(position);
+                    let b = b.1.translation().xy().distance_squared(position);
+                    a.total_cmp(&b)
+                })
+                .map(|spawner| (spawner.0, spawner.1.translation()))
+        };
+
+        current_level.and_then(|level| nearest(Some(level))).or_else(|| nearest(None))
+    }
 }
 
 pub fn update(
-    spawners: Query<&Transform, (With<PlayerSpawner>, Changed<Transform>)>,
+    spawners: Query<(&Transform, Entity), (With<PlayerSpawner>, Changed<Transform>)>,
     mut player: Query<&mut Transform, (With<Player>, Without<PlayerSpawner>)>,
-    mut has_spawned: Local<bool>,
+    levels: Query<&LoadedLevel>,
+    level_assets: Res<Assets<DeLevel>>,
+    mut checkpoint: ResMut<Checkpoint>,
+    mut player_placed: ResMut<PlayerPlaced>,
 ) {
-    if spawners.is_empty() || *has_spawned { return }
+    if player_placed.placed { return }
 
-    let spawner_transform = spawners.iter().next().unwrap();
+    let Some((spawner_transform, spawner)) = spawners.iter().next() else {
+        let mut level_assets = levels.iter().map(|level| level_assets.get(level.handle.id()));
+        let no_spawners = !levels.is_empty() && level_assets.all(|level| {
+            level.is_some_and(|level| level.objects::<PlayerSpawner>().is_empty())
+        });
+        if no_spawners {
+            player_placed.placed = true;
+        }
+        return
+    };
     let mut player_transform = player.single_mut();
 
     *player_transform = *spawner_transform;
-    *has_spawned = true;
+    checkpoint.spawner = Some(spawner);
+    player_placed.placed = true;
+}
+
+pub fn activate_checkpoints(
+    spawners: Query<(&CollidingEntities, Entity),
+        (With<PlayerSpawner>, Changed<CollidingEntities>)>,
+    parents: Query<&Parent>,
+    players: Query<Entity, With<Player>>,
+    mut checkpoint: ResMut<Checkpoint>,
+) {
+    let player = players.single();
+
+    for spawner in &spawners {
+        let touched_by_player = spawner.0.iter().any(|colliding| {
+            parents.iter_ancestors(colliding).any(|ancestor| ancestor == player)
+        });
+
+        if touched_by_player && checkpoint.spawner != Some(spawner.1) {
+            checkpoint.spawner = Some(spawner.1);
+            info!("checkpoint reached: {:?}", spawner.1);
+        }
+    }
+}
+
+pub fn respawn_player(
+    mut player: Query<(
+        &mut Respawning,
+        &mut Transform,
+        &mut Velocity,
+        &mut Visibility,
+        &SentenceStructure,
+        Entity,
+    ), With<Player>>,
+    mut word_objects: Query<(&WordObject, &mut Transform), Without<Player>>,
+    children: Query<&Children>,
+    assets: Res<MiscAssets>,
+    time: Res<Time>,
+    mut commands: Commands,
+) {
+    let Ok((mut respawning, mut transform, mut velocity, mut visibility, sentence, entity))
+        = player.get_single_mut() else { return };
+
+    if !respawning.timer.tick(time.delta()).finished() { return }
+
+    transform.translation = respawning.at;
+    *velocity = Velocity::zero();
+    *visibility = Visibility::Inherited;
+
+    // don't keep growing or shrinking from where the player died
+    for child in children.iter_descendants(entity) {
+        let Ok(mut word_object) = word_objects.get_mut(child) else { continue };
+        let target_scale = word_object.0.adjectives.target_scale();
+        word_object.1.scale.x = target_scale.x;
+        word_object.1.scale.y = target_scale.y;
+    }
+
+    let mut player_commands = commands.entity(entity);
+    player_commands.remove::<Respawning>();
+    if sentence.valid {
+        player_commands.remove::<RigidBodyDisabled>();
+    }
+
+    if respawning.effect {
+        commands.spawn((
+            SpriteBundle {
+                transform: Transform::from_translation(respawning.at.xy().extend(1.)),
+                texture: assets.square_pale.clone(),
+                ..default()
+            },
+            RespawnEffect { timer: Timer::from_seconds(0.4, TimerMode::Once) },
+            Name::new("Respawn Effect"),
+        ));
+    }
+}
+
+pub fn fade_respawn_effects(
+    mut effects: Query<(&mut RespawnEffect, &mut Sprite, &mut Transform, Entity)>,
+    time: Res<Time>,
+    mut commands: Commands,
+) {
+    for mut effect in &mut effects {
+        effect.0.timer.tick(time.delta());
+        let progress = effect.0.timer.percent();
+
+        effect.1.color = Color::WHITE.with_a(1. - progress);
+        effect.2.scale = Vec3::splat(1. + progress * 3.);
+
+        if effect.0.timer.finished() {
+            commands.entity(effect.3).despawn_recursive();
+        }
+    }
 }
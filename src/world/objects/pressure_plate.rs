@@ -0,0 +1,119 @@
+use crate::{prelude::*, word::spawn::WordObject};
+
+use super::{WorldObject, SignalSource, signal::source_color};
+
+/// A plate on the ground that's on while something heavy or big enough stands on it.
+#[derive(Default, Component)]
+pub struct PressurePlate {
+    pub weight: PlateWeight,
+}
+
+/// What's enough to press a plate. A word object presses the plate if it meets either limit
+/// that's set, and anything presses it if neither is.
+#[derive(Debug, Default, Copy, Clone, Serialize, Deserialize)]
+pub struct PlateWeight {
+    /// The mass of the body the word object is part of.
+    #[serde(default)] pub min_mass: Option<f32>,
+    /// How many times bigger than a 16×16 word object its collider has to be, by area, so
+    /// long words and grown ones both count.
+    #[serde(default)] pub min_size: Option<f32>,
+}
+
+#[derive(Default, Bundle)]
+pub struct PressurePlateBundle {
+    plate: PressurePlate,
+    source: SignalSource,
+    sprite: SpriteBundle,
+    collider: Collider,
+    colliding: CollidingEntities,
+    rigidbody: RigidBody,
+    events: ActiveEvents,
+    sensor: Sensor,
+    name: Name,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PressurePlateInWorld {
+    pub transform: Transform,
+    #[serde(default)] pub weight: PlateWeight,
+}
+
+impl WorldObject for PressurePlate {
+    type Bundle = PressurePlateBundle;
+    type InWorld = PressurePlateInWorld;
+
+    const KEY: &'static str = "pressure_plates";
+    const NAME: &'static str = "Pressure Plates";
+
+    fn bundle(in_world: &PressurePlateInWorld, assets: &MiscAssets) -> Self::Bundle {
+        PressurePlateBundle {
+            plate: PressurePlate { weight: in_world.weight },
+            sprite: SpriteBundle {
+                transform: in_world.transform,
+                texture: assets.square_yellow.clone(),
+                sprite: Sprite {
+                    custom_size: Some(Vec2::new(16., 4.)),
+                    color: source_color(false),
+                    ..default()
+                },
+                ..default()
+            },
+            collider: Collider::cuboid(8., 2.),
+            rigidbody: RigidBody::Fixed,
+            events: ActiveEvents::all(),
+            name: Name::new("Pressure Plate"),
+            ..default()
+        }
+    }
+
+    fn in_world(&self, transform: &Transform) -> PressurePlateInWorld {
+        PressurePlateInWorld { transform: *transform, weight: self.weight }
+    }
+
+    fn placed_at(position: Vec2) -> Option<PressurePlateInWorld> {
+        Some(PressurePlateInWorld {
+            // sits on the floor of the tile that was clicked
+            transform: Transform::from_translation(Vec3::new(position.x, position.y - 6., -1.)),
+            weight: PlateWeight { min_mass: None, min_size: Some(4.) },
+        })
+    }
+}
+
+impl PlateWeight {
+    fn pressed_by(&self, size: f32, mass: f32) -> bool {
+        if self.min_mass.is_none() && self.min_size.is_none() { return true }
+
+        self.min_mass.is_some_and(|min_mass| mass >= min_mass) ||
+            self.min_size.is_some_and(|min_size| size >= min_size)
+    }
+}
+
+pub fn update(
+    mut plates: Query<(&PressurePlate, &CollidingEntities, &mut SignalSource, &mut Sprite)>,
+    word_objects: Query<&Collider, With<WordObject>>,
+    bodies: Query<&ReadMassProperties>,
+    parents: Query<&Parent>,
+) {
+    for (plate, colliding, mut source, mut sprite) in &mut plates {
+        let pressed = colliding.iter().any(|colliding| {
+            let Ok(shape) = word_objects.get(colliding) else { return false };
+
+            // colliders are already scaled with the word object
+            let size = shape.as_cuboid()
+                .map_or(1., |cuboid| {
+                    let half_extents = cuboid.half_extents();
+                    half_extents.x * half_extents.y * 4. / (16. * 16.)
+                });
+            let mass = parents.iter_ancestors(colliding)
+                .find_map(|ancestor| bodies.get(ancestor).ok())
+                .map_or(0., |mass| mass.mass);
+
+            plate.weight.pressed_by(size, mass)
+        });
+
+        if source.on != pressed {
+            source.on = pressed;
+            sprite.color = source_color(pressed);
+        }
+    }
+}
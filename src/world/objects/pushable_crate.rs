@@ -0,0 +1,113 @@
+use crate::prelude::*;
+use crate::word::{SentenceStructure, SentenceStructureChanged, WordID};
+use crate::word::ui::OwnSentenceBar;
+
+use super::WorldObject;
+
+/// A box the player can push around. It has its own sentence, which is just "crate" unless
+/// it's been given words, so words like "wide" work on it the same way they do on the
+/// player.
+#[derive(Default, Component)]
+pub struct Crate {
+    /// The words describing the crate. Kept up to date with its sentence, so they're saved.
+    pub words: Vec<WordID>,
+    /// Whether the crate gets its own sentence bar that words can be dropped into.
+    pub accepts_words: bool,
+}
+
+#[derive(Bundle)]
+pub struct CrateBundle {
+    pushable: Crate,
+    sentence: SentenceStructure,
+    spatial: SpatialBundle,
+    rigidbody: RigidBody,
+    velocity: Velocity,
+    force: ExternalForce,
+    mass: ReadMassProperties,
+    locked_axes: LockedAxes,
+    name: Name,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct CrateInWorld {
+    pub transform: Transform,
+    /// The words before "crate" in its sentence.
+    #[serde(default)] pub words: Vec<WordID>,
+    #[serde(default)] pub accepts_words: bool,
+}
+
+impl WorldObject for Crate {
+    type Bundle = CrateBundle;
+    type InWorld = CrateInWorld;
+
+    const KEY: &'static str = "crates";
+    const NAME: &'static str = "Crates";
+
+    fn bundle(in_world: &CrateInWorld, _: &MiscAssets) -> Self::Bundle {
+        let mut words = in_world.words.clone();
+        words.push(WordID::Crate);
+        let sentence = SentenceStructure::from_words(&words);
+
+        CrateBundle {
+            pushable: Crate {
+                words: in_world.words.clone(),
+                accepts_words: in_world.accepts_words,
+            },
+            sentence,
+            spatial: SpatialBundle::from_transform(in_world.transform),
+            rigidbody: RigidBody::Dynamic,
+            velocity: Velocity::zero(),
+            force: ExternalForce::default(),
+            mass: ReadMassProperties::default(),
+            locked_axes: LockedAxes::ROTATION_LOCKED,
+            name: Name::new("Crate"),
+        }
+    }
+
+    fn in_world(&self, transform: &Transform) -> CrateInWorld {
+        CrateInWorld {
+            transform: *transform,
+            words: self.words.clone(),
+            accepts_words: self.accepts_words,
+        }
+    }
+
+    fn placed_at(position: Vec2) -> Option<CrateInWorld> {
+        Some(CrateInWorld {
+            transform: Transform::from_translation(position.extend(0.)),
+            words: Vec::new(),
+            accepts_words: true,
+        })
+    }
+}
+
+/// Spawns the word objects of new crates, and gives the ones that accept words their
+/// sentence bar.
+pub fn setup_crates(
+    crates: Query<(&Crate, Entity), Added<Crate>>,
+    mut structure_changes: EventWriter<SentenceStructureChanged>,
+    mut commands: Commands,
+) {
+    for (pushable, entity) in &crates {
+        if pushable.accepts_words {
+            commands.entity(entity).insert(OwnSentenceBar { noun: WordID::Crate });
+        }
+        structure_changes.send(SentenceStructureChanged { on: entity });
+    }
+}
+
+pub fn remember_crate_words(
+    mut crates: Query<(&mut Crate, &SentenceStructure), Changed<SentenceStructure>>,
+) {
+    for (mut pushable, sentence) in &mut crates {
+        if !sentence.valid { continue }
+
+        let mut words = sentence.own_words();
+        if words.last() == Some(&WordID::Crate) {
+            words.pop();
+        }
+        if pushable.words != words {
+            pushable.words = words;
+        }
+    }
+}
@@ -0,0 +1,256 @@
+// Every kind of world object is registered once with `register_world_object`, and saving,
+// loading and the editor go through the registry instead of knowing about each kind.
+//
+// Level files keep one list per kind of object, next to the tiles. Each registration only
+// reads its own list out of the file and skips the rest, so the lists can be stored without
+// knowing their types. Each object in a list is saved with its `ObjectId`.
+
+use std::any::Any;
+use std::marker::PhantomData;
+use std::sync::{Arc, RwLock, RwLockReadGuard};
+
+use bevy::ecs::world::{EntityRef, EntityWorldMut};
+use ron::ser::Compound;
+use serde::de::{DeserializeOwned, DeserializeSeed, IgnoredAny, MapAccess, Visitor};
+use serde::ser::SerializeStruct;
+
+use crate::prelude::*;
+use crate::world::streaming::{LevelMemory, spawn_objects};
+
+use super::{WorldObject, ObjectId, Placed};
+
+pub type ObjectList = Box<dyn Any + Send + Sync>;
+pub type LevelWriter<'a, 'b> = Compound<'a, &'b mut Vec<u8>>;
+
+/// What save, load and the editor need to know about one kind of world object.
+pub struct ObjectRegistration {
+    pub key: &'static str,
+    pub name: &'static str,
+    /// Whether the object can be clicked into a level in the editor.
+    pub placeable: bool,
+    load: fn(&[u8]) -> std::io::Result<Option<ObjectList>>,
+    save: fn(&ObjectList, &mut LevelWriter) -> ron::Result<()>,
+    new_list: fn() -> ObjectList,
+    push: fn(EntityRef, &mut ObjectList) -> bool,
+    spawn: fn(&ObjectList, &MiscAssets, Entity, &LevelMemory, &mut Commands),
+    place: fn(Vec2, &MiscAssets, &mut Commands) -> Option<Entity>,
+    target: fn(EntityRef) -> Option<ObjectId>,
+    set_target: fn(&mut EntityWorldMut, Option<ObjectId>) -> bool,
+}
+
+impl ObjectRegistration {
+    fn of<T: WorldObject>() -> Self {
+        Self {
+            key: T::KEY,
+            name: T::NAME,
+            placeable: T::placed_at(Vec2::ZERO).is_some(),
+            load: |bytes| {
+                let mut keys = vec![T::KEY];
+                keys.extend(T::ALIASES);
+
+                let list = match read_field::<Vec<Placed<T::InWorld>>>(bytes, &keys) {
+                    Ok(list) => list,
+                    // older level files don't have IDs, so the objects get new ones
+                    Err(err) => read_field::<Vec<T::InWorld>>(bytes, &keys)
+                        .map_err(|_| err)?
+                        .map(|list| list.into_iter()
+                            .map(|object| Placed { id: ObjectId::new(), object })
+                            .collect()),
+                };
+
+                Ok(list.map(|list| Box::new(list) as ObjectList))
+            },
+            save: |list, writer| writer.serialize_field(T::KEY, downcast::<T>(list)),
+            new_list: || Box::new(Vec::<Placed<T::InWorld>>::new()),
+            push: |entity, list| {
+                let (Some(object), Some(transform)) =
+                    (entity.get::<T>(), entity.get::<Transform>()) else { return false };
+
+                list.downcast_mut::<Vec<Placed<T::InWorld>>>().unwrap().push(Placed {
+                    id: entity.get::<ObjectId>().copied().unwrap_or_else(ObjectId::new),
+                    object: object.in_world(transform),
+                });
+                true
+            },
+            spawn: |list, assets, level, memory, commands| {
+                spawn_objects::<T>(downcast::<T>(list), assets, level, memory, commands);
+            },
+            place: |position, assets, commands| {
+                let in_world = T::placed_at(position)?;
+                Some(commands.spawn((T::bundle(&in_world, assets), ObjectId::new())).id())
+            },
+            target: |entity| entity.get::<T>()?.target(),
+            set_target: |entity, target| {
+                if !T::HAS_TARGET { return false }
+                let Some(mut object) = entity.get_mut::<T>() else { return false };
+
+                object.set_target(target);
+                true
+            },
+        }
+    }
+
+    /// Reads this object's list out of a level file, or `None` if the file doesn't have one.
+    pub fn load(&self, bytes: &[u8]) -> std::io::Result<Option<ObjectList>> {
+        (self.load)(bytes)
+    }
+
+    pub fn save(&self, list: &ObjectList, writer: &mut LevelWriter) -> ron::Result<()> {
+        (self.save)(list, writer)
+    }
+
+    pub fn new_list(&self) -> ObjectList {
+        (self.new_list)()
+    }
+
+    /// Adds `entity` to `list` if it's this kind of object.
+    pub fn push(&self, entity: EntityRef, list: &mut ObjectList) -> bool {
+        (self.push)(entity, list)
+    }
+
+    pub fn spawn(
+        &self,
+        list: &ObjectList,
+        assets: &MiscAssets,
+        level: Entity,
+        memory: &LevelMemory,
+        commands: &mut Commands,
+    ) {
+        (self.spawn)(list, assets, level, memory, commands)
+    }
+
+    /// Spawns the object the editor places at `position`, in level space.
+    pub fn place(
+        &self,
+        position: Vec2,
+        assets: &MiscAssets,
+        commands: &mut Commands,
+    ) -> Option<Entity> {
+        (self.place)(position, assets, commands)
+    }
+
+    /// The object `entity` points at, if it's this kind of object and has a target.
+    pub fn target(&self, entity: EntityRef) -> Option<ObjectId> {
+        (self.target)(entity)
+    }
+}
+
+fn downcast<T: WorldObject>(list: &ObjectList) -> &Vec<Placed<T::InWorld>> {
+    list.downcast_ref::<Vec<Placed<T::InWorld>>>().unwrap()
+}
+
+fn read_field<T: DeserializeOwned>(bytes: &[u8], keys: &[&str]) -> std::io::Result<Option<T>> {
+    let mut deserializer = ron::Deserializer::from_bytes(bytes).map_err(invalid_data)?;
+    FieldSeed::<T>::new(keys).deserialize(&mut deserializer).map_err(invalid_data)
+}
+
+fn invalid_data(err: impl std::error::Error + Send + Sync + 'static) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, err)
+}
+
+/// All registered world objects, in the order they were registered. This is shared with the
+/// level loader, which is why it's behind a lock.
+#[derive(Resource, Clone, Default)]
+pub struct WorldObjects(Arc<RwLock<Vec<ObjectRegistration>>>);
+
+impl WorldObjects {
+    pub fn read(&self) -> RwLockReadGuard<Vec<ObjectRegistration>> {
+        self.0.read().unwrap()
+    }
+
+    /// Points `entity` at `target`, or clears its target. Returns false if `entity` isn't an
+    /// object that can have a target.
+    pub fn set_target(&self, world: &mut World, entity: Entity, target: Option<ObjectId>) -> bool {
+        let Some(mut entity) = world.get_entity_mut(entity) else { return false };
+
+        self.read().iter().any(|registration| (registration.set_target)(&mut entity, target))
+    }
+}
+
+pub trait WorldObjectAppExt {
+    fn register_world_object<T: WorldObject>(&mut self) -> &mut Self;
+}
+
+impl WorldObjectAppExt for App {
+    fn register_world_object<T: WorldObject>(&mut self) -> &mut Self {
+        let objects = self.world.get_resource_or_insert_with(WorldObjects::default).clone();
+        let mut objects = objects.0.write().unwrap();
+
+        if objects.iter().any(|registration| registration.key == T::KEY) {
+            warn!("world object {} was registered twice", T::KEY);
+        } else {
+            objects.push(ObjectRegistration::of::<T>());
+        }
+
+        self
+    }
+}
+
+/// Reads one field out of a struct, ignoring all of the others. Returns `None` if none of
+/// `keys` are in the struct.
+pub struct FieldSeed<'a, T> {
+    keys: &'a [&'a str],
+    marker: PhantomData<T>,
+}
+
+impl<'a, T> FieldSeed<'a, T> {
+    pub fn new(keys: &'a [&'a str]) -> Self {
+        Self { keys, marker: PhantomData }
+    }
+}
+
+impl<'de, T: DeserializeOwned> DeserializeSeed<'de> for FieldSeed<'_, T> {
+    type Value = Option<T>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Option<T>, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_struct("DeLevel", &[], self)
+    }
+}
+
+impl<'de, T: DeserializeOwned> Visitor<'de> for FieldSeed<'_, T> {
+    type Value = Option<T>;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(formatter, "a level")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Option<T>, A::Error> {
+        let mut value = None;
+
+        while let Some(FieldName(key)) = map.next_key()? {
+            if self.keys.contains(&&*key) && value.is_none() {
+                value = Some(map.next_value()?);
+            } else {
+                map.next_value::<IgnoredAny>()?;
+            }
+        }
+
+        Ok(value)
+    }
+}
+
+/// Struct fields in ron are identifiers, not strings.
+struct FieldName(String);
+
+impl<'de> Deserialize<'de> for FieldName {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct FieldNameVisitor;
+
+        impl<'de> Visitor<'de> for FieldNameVisitor {
+            type Value = FieldName;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                write!(formatter, "a field name")
+            }
+
+            fn visit_str<E: serde::de::Error>(self, name: &str) -> Result<FieldName, E> {
+                Ok(FieldName(name.to_string()))
+            }
+        }
+
+        deserializer.deserialize_identifier(FieldNameVisitor)
+    }
+}
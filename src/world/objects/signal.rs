@@ -0,0 +1,43 @@
+// Signals let objects switch each other on and off. Sources like pressure plates and switches
+// have a `SignalSource`, and receivers like doors, fans and lock zones point at the source
+// they listen to with their target, which is picked in the editor like any other target.
+
+use crate::prelude::*;
+
+use super::{WorldObject, ObjectId};
+
+/// Whether a pressure plate or switch is on.
+#[derive(Component, Default, Debug)]
+pub struct SignalSource {
+    pub on: bool,
+}
+
+/// A world object that's switched on and off by the source it targets. Receivers without a
+/// source keep whatever state they were spawned with.
+pub trait SignalReceiver: WorldObject {
+    fn powered(&self) -> bool;
+    fn set_powered(&mut self, powered: bool);
+}
+
+pub fn receive_signals<T: SignalReceiver>(
+    sources: Query<(&ObjectId, &SignalSource)>,
+    mut receivers: Query<&mut T>,
+) {
+    let sources: HashMap<ObjectId, bool> = sources.iter()
+        .map(|(id, source)| (*id, source.on))
+        .collect();
+
+    for mut receiver in &mut receivers {
+        let Some(on) = receiver.target().and_then(|source| sources.get(&source)) else { continue };
+
+        // only writing when it changes keeps change detection quiet
+        if receiver.powered() != *on {
+            receiver.set_powered(*on);
+        }
+    }
+}
+
+/// The color of a source's sprite when it's on and off.
+pub fn source_color(on: bool) -> Color {
+    if on { Color::WHITE } else { Color::GRAY }
+}
@@ -0,0 +1,90 @@
+use crate::{prelude::*, word::spawn::WordObject};
+
+use super::{WorldObject, SignalSource, signal::source_color};
+
+/// Flips between on and off each time something touches it.
+#[derive(Default, Component)]
+pub struct Switch {
+    pub starts_on: bool,
+    touched: bool,
+}
+
+#[derive(Default, Bundle)]
+pub struct SwitchBundle {
+    switch: Switch,
+    source: SignalSource,
+    sprite: SpriteBundle,
+    collider: Collider,
+    colliding: CollidingEntities,
+    rigidbody: RigidBody,
+    events: ActiveEvents,
+    sensor: Sensor,
+    name: Name,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SwitchInWorld {
+    pub transform: Transform,
+    /// Whether the switch starts out on.
+    #[serde(default)] pub on: bool,
+}
+
+impl WorldObject for Switch {
+    type Bundle = SwitchBundle;
+    type InWorld = SwitchInWorld;
+
+    const KEY: &'static str = "switches";
+    const NAME: &'static str = "Switches";
+
+    fn bundle(in_world: &SwitchInWorld, assets: &MiscAssets) -> Self::Bundle {
+        SwitchBundle {
+            switch: Switch { starts_on: in_world.on, touched: false },
+            source: SignalSource { on: in_world.on },
+            sprite: SpriteBundle {
+                transform: in_world.transform,
+                texture: assets.square_pink.clone(),
+                sprite: Sprite {
+                    custom_size: Some(Vec2::splat(8.)),
+                    color: source_color(in_world.on),
+                    ..default()
+                },
+                ..default()
+            },
+            collider: Collider::cuboid(4., 4.),
+            rigidbody: RigidBody::Fixed,
+            events: ActiveEvents::all(),
+            name: Name::new("Switch"),
+            ..default()
+        }
+    }
+
+    fn in_world(&self, transform: &Transform) -> SwitchInWorld {
+        SwitchInWorld { transform: *transform, on: self.starts_on }
+    }
+
+    fn placed_at(position: Vec2) -> Option<SwitchInWorld> {
+        Some(SwitchInWorld {
+            transform: Transform::from_translation(position.extend(-1.)),
+            on: false,
+        })
+    }
+}
+
+pub fn update(
+    mut switches: Query<
+        (&mut Switch, &CollidingEntities, &mut SignalSource, &mut Sprite),
+        Changed<CollidingEntities>,
+    >,
+    word_objects: Query<(), With<WordObject>>,
+) {
+    for (mut switch, colliding, mut source, mut sprite) in &mut switches {
+        let touched = colliding.iter().any(|colliding| word_objects.contains(colliding));
+
+        // only flips when something starts touching it, not for as long as it does
+        if touched && !switch.touched {
+            source.on = !source.on;
+            sprite.color = source_color(source.on);
+        }
+        switch.touched = touched;
+    }
+}
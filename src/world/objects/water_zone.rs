@@ -0,0 +1,161 @@
+use crate::{prelude::*, word::spawn::WordObject};
+
+use super::{WorldObject, Zone, ZoneArea};
+
+/// A body of water. Word objects in it are pushed up by how much of them is under the
+/// surface, so whether something floats comes down to its mass against its size: a baby
+/// floats, a horse sinks, and making something wide makes it float better. Everything in it
+/// is slowed down too.
+#[derive(Default, Component)]
+pub struct WaterZone {
+    /// The mass of a square pixel of water. Things lighter than this for their size float.
+    pub density: f32,
+    /// How much of their speed things in the water lose each second.
+    pub drag: f32,
+    pub area: ZoneArea,
+}
+
+#[derive(Default, Bundle)]
+pub struct WaterZoneBundle {
+    water: WaterZone,
+    sprite: SpriteBundle,
+    collider: Collider,
+    colliding: CollidingEntities,
+    rigidbody: RigidBody,
+    events: ActiveEvents,
+    sensor: Sensor,
+    name: Name,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WaterZoneInWorld {
+    pub transform: Transform,
+    #[serde(default = "default_density")] pub density: f32,
+    #[serde(default = "default_drag")] pub drag: f32,
+    pub area: ZoneArea,
+}
+
+fn default_density() -> f32 { 0.85 }
+fn default_drag() -> f32 { 3. }
+
+impl WorldObject for WaterZone {
+    type Bundle = WaterZoneBundle;
+    type InWorld = WaterZoneInWorld;
+
+    const KEY: &'static str = "water_zones";
+    const NAME: &'static str = "Water";
+
+    fn bundle(in_world: &WaterZoneInWorld, assets: &MiscAssets) -> Self::Bundle {
+        WaterZoneBundle {
+            sprite: SpriteBundle {
+                transform: in_world.transform,
+                texture: assets.square_pale.clone(),
+                sprite: Sprite {
+                    color: Color::rgba(0.2, 0.4, 0.9, 0.5),
+                    custom_size: Some(in_world.area.bounds().size()),
+                    anchor: in_world.area.sprite_anchor(),
+                    ..default()
+                },
+                ..default()
+            },
+            collider: in_world.area.collider(),
+            water: WaterZone {
+                density: in_world.density,
+                drag: in_world.drag,
+                area: in_world.area.clone(),
+            },
+            rigidbody: RigidBody::Fixed,
+            events: ActiveEvents::all(),
+            name: Name::new("Water"),
+            ..default()
+        }
+    }
+
+    fn in_world(&self, transform: &Transform) -> WaterZoneInWorld {
+        WaterZoneInWorld {
+            transform: *transform,
+            density: self.density,
+            drag: self.drag,
+            area: self.area.clone(),
+        }
+    }
+
+    fn placed_at(position: Vec2) -> Option<WaterZoneInWorld> {
+        Some(WaterZoneInWorld {
+            // in front of what's in it, since it's see-through
+            transform: Transform::from_translation(position.extend(1.)),
+            density: default_density(),
+            drag: default_drag(),
+            area: ZoneArea::Rect { size: Vec2::new(64., 32.) },
+        })
+    }
+}
+
+impl Zone for WaterZone {
+    fn area(&self) -> &ZoneArea { &self.area }
+    fn area_mut(&mut self) -> &mut ZoneArea { &mut self.area }
+}
+
+impl WaterZone {
+    /// The height of the water's surface in world space.
+    pub fn surface(&self, transform: &GlobalTransform) -> f32 {
+        self.area.corners().into_iter()
+            .map(|corner| transform.transform_point(corner.extend(0.)).y)
+            .fold(f32::NEG_INFINITY, f32::max)
+    }
+}
+
+/// Pushes word objects in water up, and slows down the bodies they're part of. Like force
+/// zones, what was added to a body's force last frame is taken back out first.
+pub fn update(
+    waters: Query<(&WaterZone, &GlobalTransform, &CollidingEntities)>,
+    word_objects: Query<(&Collider, &GlobalTransform), With<WordObject>>,
+    mut bodies: Query<(&mut ExternalForce, &mut Velocity), With<RigidBody>>,
+    parents: Query<&Parent>,
+    rapier_config: Res<RapierConfiguration>,
+    time: Res<Time>,
+    mut applied: Local<HashMap<Entity, Vec2>>,
+) {
+    let mut forces = HashMap::<Entity, Vec2>::new();
+    let mut slowed = HashMap::<Entity, f32>::new();
+
+    for (water, water_transform, colliding) in &waters {
+        let surface = water.surface(water_transform);
+
+        for collider in colliding.iter() {
+            let Ok((shape, transform)) = word_objects.get(collider) else { continue };
+            let Some(cuboid) = shape.as_cuboid() else { continue };
+            let Some(body) = parents.iter_ancestors(collider)
+                .find(|entity| bodies.contains(*entity)) else { continue };
+
+            let half_extents = cuboid.half_extents();
+            let bottom = transform.translation().y - half_extents.y;
+            let submerged = ((surface - bottom) / (half_extents.y * 2.)).clamp(0., 1.);
+            let volume = half_extents.x * half_extents.y * 4. * submerged;
+
+            // the weight of the water it pushes out of the way, pointing up
+            *forces.entry(body).or_default() -= rapier_config.gravity * water.density * volume;
+
+            let drag = slowed.entry(body).or_default();
+            *drag = drag.max(water.drag * submerged);
+        }
+    }
+
+    for (body, old_force) in applied.drain() {
+        if let Ok(mut body) = bodies.get_mut(body) {
+            body.0.force -= old_force;
+        }
+    }
+    for (body, force) in &forces {
+        if let Ok(mut body) = bodies.get_mut(*body) {
+            body.0.force += *force;
+        }
+    }
+    *applied = forces;
+
+    for (body, drag) in slowed {
+        if let Ok(mut body) = bodies.get_mut(body) {
+            body.1.linvel *= (1. - drag * time.delta_seconds()).max(0.);
+        }
+    }
+}
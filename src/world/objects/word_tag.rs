@@ -32,6 +32,9 @@ impl WorldObject for WordTag {
     type Bundle = WordTagBundle;
     type InWorld = WordTagInWorld;
 
+    const KEY: &'static str = "word_tags";
+    const NAME: &'static str = "Word Tags";
+
     fn bundle(in_world: &WordTagInWorld, assets: &MiscAssets) -> WordTagBundle {
         WordTagBundle {
             word_tag: WordTag { word_id: in_world.word_id },
@@ -47,6 +50,17 @@ impl WorldObject for WordTag {
             ..default()
         }
     }
+
+    fn in_world(&self, transform: &Transform) -> WordTagInWorld {
+        WordTagInWorld { word_id: self.word_id, transform: *transform }
+    }
+
+    fn placed_at(position: Vec2) -> Option<WordTagInWorld> {
+        Some(WordTagInWorld {
+            word_id: WordID::Fast,
+            transform: Transform::from_translation(position.extend(0.)),
+        })
+    }
 }
 
 pub fn update(
@@ -0,0 +1,150 @@
+use crate::{prelude::*, word::{*, spawn::WordObject}};
+
+use super::{WorldObject, ObjectId, SignalReceiver, Zone, ZoneArea};
+
+/// Adds a word to the sentence of anything inside it, like a fan does with "fluttering".
+/// The word is locked while it's there, and taken back out when the object leaves.
+#[derive(Default, Component)]
+pub struct WordZone {
+    /// Has to be an adjective.
+    pub word: WordID,
+    /// The source that turns the zone on and off. Zones without one are always on.
+    pub signal: Option<ObjectId>,
+    pub on: bool,
+    pub area: ZoneArea,
+}
+
+#[derive(Default, Bundle)]
+pub struct WordZoneBundle {
+    zone: WordZone,
+    sprite: SpriteBundle,
+    collider: Collider,
+    colliding: CollidingEntities,
+    rigidbody: RigidBody,
+    events: ActiveEvents,
+    sensor: Sensor,
+    name: Name,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WordZoneInWorld {
+    pub transform: Transform,
+    pub word: WordID,
+    #[serde(default)] pub signal: Option<ObjectId>,
+    /// Older zones were sized by the scale of `transform` instead.
+    #[serde(default)] pub area: Option<ZoneArea>,
+}
+
+impl WorldObject for WordZone {
+    type Bundle = WordZoneBundle;
+    type InWorld = WordZoneInWorld;
+
+    const KEY: &'static str = "word_zones";
+    const NAME: &'static str = "Word Zones";
+
+    fn bundle(in_world: &WordZoneInWorld, assets: &MiscAssets) -> Self::Bundle {
+        let (area, transform) = ZoneArea::from_legacy(&in_world.area, &in_world.transform);
+
+        WordZoneBundle {
+            sprite: SpriteBundle {
+                transform,
+                texture: assets.square_pale.clone(),
+                sprite: Sprite {
+                    color: Color::rgba(0.5, 0.8, 1., 0.4),
+                    custom_size: Some(area.bounds().size()),
+                    anchor: area.sprite_anchor(),
+                    ..default()
+                },
+                ..default()
+            },
+            collider: area.collider(),
+            zone: WordZone { word: in_world.word, signal: in_world.signal, on: true, area },
+            rigidbody: RigidBody::Fixed,
+            events: ActiveEvents::all(),
+            name: Name::new(format!("Word Zone ({})", in_world.word.forms().basic)),
+            ..default()
+        }
+    }
+
+    fn in_world(&self, transform: &Transform) -> WordZoneInWorld {
+        WordZoneInWorld {
+            transform: *transform,
+            word: self.word,
+            signal: self.signal,
+            area: Some(self.area.clone()),
+        }
+    }
+
+    fn placed_at(position: Vec2) -> Option<WordZoneInWorld> {
+        Some(WordZoneInWorld {
+            transform: Transform::from_translation(position.extend(-2.)),
+            word: WordID::Wide,
+            signal: None,
+            area: Some(ZoneArea::Rect { size: Vec2::splat(32.) }),
+        })
+    }
+
+    const HAS_TARGET: bool = true;
+
+    fn target(&self) -> Option<ObjectId> { self.signal }
+
+    fn set_target(&mut self, target: Option<ObjectId>) { self.signal = target }
+}
+
+impl Zone for WordZone {
+    fn area(&self) -> &ZoneArea { &self.area }
+    fn area_mut(&mut self) -> &mut ZoneArea { &mut self.area }
+}
+
+impl SignalReceiver for WordZone {
+    fn powered(&self) -> bool { self.on }
+    fn set_powered(&mut self, powered: bool) { self.on = powered }
+}
+
+/// Adds each zone's word to the sentences of the word objects inside it, and takes out the
+/// zone words that sentences shouldn't have anymore. Only words some zone gives out are
+/// taken out, so words injected by other things, like fans, are left alone.
+pub fn update(
+    zones: Query<(&WordZone, &CollidingEntities)>,
+    word_objects: Query<&WordObject>,
+    mut sentences: Query<(&mut SentenceStructure, Entity)>,
+    mut structure_changes: EventWriter<SentenceStructureChanged>,
+) {
+    let zone_words: HashSet<WordID> = zones.iter()
+        .map(|zone| zone.0.word)
+        .filter(|word| word.part_of_speech().contains(&PartOfSpeech::Adjective))
+        .collect();
+    if zone_words.is_empty() { return }
+
+    let mut wanted = HashMap::<Entity, HashSet<WordID>>::new();
+    for (zone, colliding) in zones.iter().filter(|zone| zone.0.on) {
+        if !zone_words.contains(&zone.word) { continue }
+
+        for word_object in colliding.iter().filter_map(|entity| word_objects.get(entity).ok()) {
+            wanted.entry(word_object.sentence).or_default().insert(zone.word);
+        }
+    }
+
+    for (mut sentence, entity) in &mut sentences {
+        if !sentence.valid { continue }
+
+        // only borrowed mutably when something changes, so the sentence isn't marked as
+        // changed every frame
+        let wanted = wanted.get(&entity);
+        let mut changed = false;
+
+        for &word in &zone_words {
+            if wanted.is_some_and(|wanted| wanted.contains(&word)) {
+                if !sentence.contains_word(word) {
+                    changed |= sentence.inject_adjective(word);
+                }
+            } else if sentence.contains_word(word) && sentence.remove_injected_adjective(word) {
+                changed = true;
+            }
+        }
+
+        if changed {
+            structure_changes.send(SentenceStructureChanged { on: entity });
+        }
+    }
+}
@@ -0,0 +1,148 @@
+use crate::prelude::*;
+use bevy::sprite::Anchor;
+
+use super::WorldObject;
+
+/// The area a zone covers, around its position. Zones used to get their size from the scale
+/// of their transform, so older level files are read with `ZoneArea::from_legacy`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ZoneArea {
+    /// A rectangle centered on the zone, `size` pixels big.
+    Rect { size: Vec2 },
+    /// A polygon with corners at `points`, relative to the zone. Concave polygons are filled
+    /// in, since colliders are made from their convex hull.
+    Polygon { points: Vec<Vec2> },
+}
+
+impl Default for ZoneArea {
+    fn default() -> Self {
+        ZoneArea::Rect { size: Vec2::splat(16.) }
+    }
+}
+
+impl ZoneArea {
+    /// The smallest a rectangle can be dragged down to, and the grid its size snaps to.
+    const SNAP: f32 = 8.;
+
+    /// The area saved with a zone, or one made from the scale of `transform` for zones from
+    /// before areas were saved. Also returns `transform` without its scale, since the area
+    /// is what sizes the zone now.
+    pub fn from_legacy(area: &Option<ZoneArea>, transform: &Transform) -> (ZoneArea, Transform) {
+        let area = area.clone().unwrap_or_else(|| {
+            ZoneArea::Rect { size: transform.scale.xy().abs() * 16. }
+        });
+        (area, transform.with_scale(Vec3::ONE))
+    }
+
+    pub fn collider(&self) -> Collider {
+        match self {
+            ZoneArea::Rect { size } => Collider::cuboid(size.x / 2., size.y / 2.),
+            ZoneArea::Polygon { points } => Collider::convex_hull(points)
+                .unwrap_or_else(|| Collider::cuboid(Self::SNAP / 2., Self::SNAP / 2.)),
+        }
+    }
+
+    /// The rectangle around the area, relative to the zone. Polygons aren't centered on the
+    /// zone once their corners have been dragged around.
+    pub fn bounds(&self) -> Rect {
+        let corners = self.corners();
+        let min = corners.iter().copied().reduce(Vec2::min).unwrap_or_default();
+        let max = corners.iter().copied().reduce(Vec2::max).unwrap_or_default();
+        Rect::from_corners(min, max)
+    }
+
+    /// Where a sprite the size of `bounds` has to be anchored to cover the area.
+    pub fn sprite_anchor(&self) -> Anchor {
+        let bounds = self.bounds();
+        Anchor::Custom(-bounds.center() / bounds.size().max(Vec2::ONE))
+    }
+
+    /// The corners of the area's outline in order, relative to the zone.
+    pub fn corners(&self) -> Vec<Vec2> {
+        match self {
+            ZoneArea::Rect { size } => {
+                let half = *size / 2.;
+                vec![-half, Vec2::new(half.x, -half.y), half, Vec2::new(-half.x, half.y)]
+            },
+            ZoneArea::Polygon { points } => points.clone(),
+        }
+    }
+
+    /// Moves corner `index` to `to`, both relative to the zone. Rectangles keep the opposite
+    /// corner where it is, so they return how far their center moved, which the zone has to
+    /// be moved by.
+    pub fn drag_corner(&mut self, index: usize, to: Vec2) -> Vec2 {
+        match self {
+            ZoneArea::Rect { size } => {
+                let opposite = -(*size / 2.) * Self::corner_sign(index);
+                let to = (to / Self::SNAP).round() * Self::SNAP;
+
+                *size = (to - opposite).abs().max(Vec2::splat(Self::SNAP));
+                opposite + *size / 2. * (to - opposite).signum()
+            },
+            ZoneArea::Polygon { points } => {
+                if let Some(point) = points.get_mut(index) {
+                    *point = to;
+                }
+                Vec2::ZERO
+            },
+        }
+    }
+
+    /// Turns a rectangle into a polygon with its corners, and adds a corner to a polygon
+    /// after corner `index`, halfway along the edge to the next one.
+    pub fn add_corner(&mut self, index: usize) {
+        let mut points = self.corners();
+        if points.is_empty() { return }
+
+        if let ZoneArea::Polygon { .. } = self {
+            let next = points[(index + 1) % points.len()];
+            points.insert(index + 1, (points[index % points.len()] + next) / 2.);
+        }
+        *self = ZoneArea::Polygon { points };
+    }
+
+    /// Takes corner `index` out of a polygon, as long as it stays a polygon.
+    pub fn remove_corner(&mut self, index: usize) {
+        if let ZoneArea::Polygon { points } = self && points.len() > 3 && index < points.len() {
+            points.remove(index);
+        }
+    }
+
+    /// Which way from the center corner `index` of a rectangle is.
+    fn corner_sign(index: usize) -> Vec2 {
+        match index % 4 {
+            0 => Vec2::new(-1., -1.),
+            1 => Vec2::new(1., -1.),
+            2 => Vec2::new(1., 1.),
+            _ => Vec2::new(-1., 1.),
+        }
+    }
+}
+
+/// A world object that covers a `ZoneArea`, which can be resized in the editor.
+pub trait Zone: WorldObject {
+    fn area(&self) -> &ZoneArea;
+    fn area_mut(&mut self) -> &mut ZoneArea;
+
+    /// The collider for the zone's area.
+    fn collider(&self) -> Collider { self.area().collider() }
+}
+
+/// Rebuilds the colliders and sprites of zones whose area changed.
+pub fn update_areas<T: Zone>(
+    mut zones: Query<(&T, &mut Collider, Option<&mut Sprite>, Entity), Changed<T>>,
+    mut last_areas: Local<HashMap<Entity, ZoneArea>>,
+) {
+    for (zone, mut collider, sprite, entity) in &mut zones {
+        // zones also change when they're turned on and off, which doesn't need a new collider
+        if last_areas.get(&entity) == Some(zone.area()) { continue }
+        last_areas.insert(entity, zone.area().clone());
+
+        *collider = zone.collider();
+        if let Some(mut sprite) = sprite {
+            sprite.custom_size = Some(zone.area().bounds().size());
+            sprite.anchor = zone.area().sprite_anchor();
+        }
+    }
+}
@@ -1,7 +1,12 @@
 use crate::world::objects::*;
 use crate::world::*;
+use crate::world::streaming::LevelMemory;
+use serde::de::DeserializeSeed;
+
+pub struct LevelLoader {
+    pub objects: WorldObjects,
+}
 
-pub struct LevelLoader;
 impl AssetLoader for LevelLoader {
     type Asset = DeLevel;
     type Settings = ();
@@ -17,12 +22,27 @@ impl AssetLoader for LevelLoader {
             let mut bytes = Vec::new();
             reader.read_to_end(&mut bytes).await.expect("unable to read world file");
 
-            let mut world = ron::de::from_bytes::<DeLevel>(&bytes)
+            let mut deserializer = ron::Deserializer::from_bytes(&bytes)
+                .expect("unable to read world file");
+            let tiles = FieldSeed::<Grid<TileIndex>>::new(&["tiles"])
+                .deserialize(&mut deserializer)
                 .expect("unable to read world file");
 
+            let mut world = DeLevel {
+                tiles: tiles.unwrap_or_else(empty_grid),
+                objects: default(),
+            };
+
             if world.tiles.rows() == 0 || world.tiles.cols() == 0 {
                 world.tiles = Grid::new(2, 2);
             }
+
+            for registration in self.objects.read().iter() {
+                let list = registration.load(&bytes)
+                    .unwrap_or_else(|err| panic!("unable to read {}: {err}", registration.key));
+                let list = list.unwrap_or_else(|| registration.new_list());
+                world.objects.insert(registration.key, list);
+            }
             
             Ok(world)
         })
@@ -73,11 +93,15 @@ pub fn setup_world(
         ..Default::default()
     });
 
+    spawn_world(world, &mut commands);
+}
+
+pub fn spawn_world(handle: Handle<DeWorld>, commands: &mut Commands) -> Entity {
     commands.spawn((
         SpatialBundle::default(),
-        LoadedWorld { handle: world, ..default() },
+        LoadedWorld { handle, ..default() },
         Name::new("World"),
-    ));
+    )).id()
 }
 
 pub fn spawn_world_on_load(
@@ -126,7 +150,9 @@ pub fn spawn_world_on_load(
                     LoadedLevel { 
                         handle: asset_server.load(path.clone()),
                         tiles: Grid::new(0, 0),
+                        ..default()
                     },
+                    LevelMemory::default(),
                     Name::new(format!("Level {path:?}")),
                 )).set_parent(world_object.1);
 
@@ -143,6 +169,7 @@ pub fn spawn_level_on_load(
     mut tilemaps: Query<LevelQuery>,
     mut level_assets: ResMut<Assets<DeLevel>>,
     de_assets: Res<MiscAssets>,
+    objects: Res<WorldObjects>,
     asset_server: Res<AssetServer>,
     mut commands: Commands,
     mut asset_events: EventReader<AssetEvent<DeLevel>>,
@@ -176,44 +203,60 @@ pub fn spawn_level_on_load(
 
         if state != LoadState::Failed && state != LoadState::Loaded { continue }
 
-        let mut tilemaps = tilemaps.iter_mut()
+        let mut level_tilemaps = tilemaps.iter_mut()
             .filter(|l| l.loaded_level.handle.id() == asset_id)
             .collect::<Vec<_>>();
 
         let level = level_assets.get(asset_id).unwrap();
 
-        for mut tilemap in &mut tilemaps {
+        for mut tilemap in &mut level_tilemaps {
             commands.entity(tilemap.entity).despawn_descendants();
 
-            spawn_level(level, &*de_assets, &mut tilemap, &mut commands);
+            spawn_level(level, &*objects, &*de_assets, &mut tilemap, &mut commands);
         }
+
+        // only names in this level are checked, so each duplicate is warned about once
+        let level_names: HashSet<&str> = level.objects::<LevelEntry>().iter()
+            .map(|entry| entry.object.name.as_str())
+            .collect();
+        let loaded_names = tilemaps.iter()
+            .map(|tilemap| tilemap.loaded_level.handle.id())
+            .unique()
+            .filter_map(|id| level_assets.get(id))
+            .flat_map(|level| level.objects::<LevelEntry>())
+            .map(|entry| entry.object.name.as_str())
+            .filter(|name| level_names.contains(name));
+        level_entry::warn_duplicate_names(loaded_names);
     }
 }
 
 fn spawn_level(
     world: &DeLevel,
+    objects: &WorldObjects,
     assets: &MiscAssets,
     tilemap: &mut LevelQueryItem,
     commands: &mut Commands,
 ) {
-    *tilemap.size = TilemapSize { x: world.tiles.cols() as u32, y: world.tiles.rows() as u32};
+    *tilemap.size = tile_coord::tilemap_size(&world.tiles);
     *tilemap.storage = TileStorage::empty(*tilemap.size);
 
-    tilemap.loaded_level.tiles = world.tiles.clone();
+    tilemap.loaded_level.set_tiles(world.tiles.clone());
 
-    for word_tag in &world.word_tags {
-        commands.spawn(WordTag::bundle(word_tag, &assets)).set_parent(tilemap.entity);
+    if !tilemap.unloaded {
+        spawn_level_objects(world, objects, assets, tilemap.entity, &*tilemap.memory, commands);
     }
-    for lock_zone in &world.lock_zones {
-        commands.spawn(LockZone::bundle(lock_zone, &assets)).set_parent(tilemap.entity);
-    }
-    for spawner in &world.player_spanwers {
-        commands.spawn(PlayerSpawner::bundle(&spawner, &assets)).set_parent(tilemap.entity);
-    }
-    for fan in &world.fans {
-        commands.spawn(Fan::bundle(fan, &assets)).set_parent(tilemap.entity);
-    }
-    for death_zone in &world.death_zones {
-        commands.spawn(DeathZone::bundle(death_zone, &assets)).set_parent(tilemap.entity);
+}
+
+pub fn spawn_level_objects(
+    world: &DeLevel,
+    objects: &WorldObjects,
+    assets: &MiscAssets,
+    level: Entity,
+    memory: &LevelMemory,
+    commands: &mut Commands,
+) {
+    for registration in objects.read().iter() {
+        let Some(list) = world.objects.get(registration.key) else { continue };
+        registration.spawn(list, assets, level, memory, commands);
     }
 }
@@ -3,20 +3,19 @@ use crate::world::*;
 
 pub fn save_world(
     asset_server: Res<AssetServer>,
-    keyboard: Res<Input<KeyCode>>,
-    levels: Query<(&LoadedLevel, Entity)>,
+    actions: Res<Input<Action>>,
+    objects: Res<WorldObjects>,
+    levels: Query<(&LoadedLevel, Has<streaming::Unloaded>, Entity)>,
     worlds: Query<&LoadedWorld>,
     children_query: Query<&Children>,
-    word_tags: Query<(&WordTag, &Transform)>,
-    lock_zones: Query<&Transform, With<LockZone>>,
-    spawners: Query<&Transform, With<PlayerSpawner>>,
-    fans: Query<(&Fan, &Transform)>,
-    death_zones: Query<&Transform, With<DeathZone>>,
+    entries: Query<&LevelEntry>,
+    level_assets: Res<Assets<DeLevel>>,
+    ecs: &World,
 ) {
     use std::path::*;
     use std::fs::*;
 
-    if !(keyboard.pressed(CONTROL_KEY) && keyboard.just_pressed(KeyCode::S)) { return }
+    if !actions.just_pressed(Action::Save) { return }
 
     for world in &worlds {
         let world_to_save = DeWorld {
@@ -35,35 +34,30 @@ pub fn save_world(
 
     }
 
+    // levels that aren't spawned keep the entries they were loaded with
+    let unloaded_entries = levels.iter()
+        .filter(|level| level.1)
+        .filter_map(|level| level_assets.get(level.0.handle.id()))
+        .flat_map(|level| level.objects::<LevelEntry>())
+        .map(|entry| entry.object.name.as_str());
+    level_entry::warn_duplicate_names(
+        entries.iter().map(|entry| entry.name.as_str()).chain(unloaded_entries));
+
+    let objects = objects.read();
+
     for level in &levels {
-        let mut level_to_save = DeLevel::default();
-        level_to_save.tiles = level.0.tiles.clone();
-
-        for child in children_query.iter_descendants(level.1) {
-            if let Ok(word_tag) = word_tags.get(child) {
-                level_to_save.word_tags.push(WordTagInWorld {
-                    word_id: word_tag.0.word_id,
-                    transform: *word_tag.1,
-                });
-            } else if let Ok(lock_zone) = lock_zones.get(child) {
-                level_to_save.lock_zones.push(LockZoneInWorld {
-                    transform: *lock_zone,
-                });
-            } else if let Ok(spawner) = spawners.get(child) {
-                level_to_save.player_spanwers.push(PlayerSpawnerInWorld {
-                    transform: *spawner,
-                });
-            } else if let Ok(fan) = fans.get(child) {
-                level_to_save.fans.push(FanInWorld {
-                    strength: fan.0.strength,
-                    translation: fan.1.translation.xy(),
-                    rotation: fan.1.rotation.to_euler(EulerRot::XYZ).2,
-                    scale: fan.1.scale.xy(),
-                });
-            } else if let Ok(death_zone) = death_zones.get(child) {
-                level_to_save.death_zones.push(DeathZoneInWorld {
-                    transform: *death_zone,
-                });
+        if level.1 {
+            info!("not saving {:?}, it isn't spawned right now", level.0.handle.path());
+            continue;
+        }
+
+        let mut lists = objects.iter().map(|registration| registration.new_list()).collect_vec();
+
+        for child in children_query.iter_descendants(level.2) {
+            let Some(child) = ecs.get_entity(child) else { continue };
+
+            for (registration, list) in objects.iter().zip(&mut lists) {
+                if registration.push(child, list) { break }
             }
         }
 
@@ -72,9 +66,29 @@ pub fn save_world(
 
         info!("saving level asset {file_path:?}");
 
-        let config = PrettyConfig::new();
-        let serialized_level = ron::ser::to_string_pretty(&level_to_save, config)
+        let serialized_level = serialize_level(&level.0.tiles, &objects, &lists)
             .expect("unable to serialize level");
         write(file_path, &*serialized_level).expect("unable to write {file_path:?}");
     }
 }
+
+/// Writes a level file with the tiles, and each registered object's list after them.
+fn serialize_level(
+    tiles: &Grid<TileIndex>,
+    objects: &[ObjectRegistration],
+    lists: &[ObjectList],
+) -> ron::Result<Vec<u8>> {
+    use serde::ser::{Serializer, SerializeStruct};
+
+    let mut output = Vec::new();
+    let mut serializer = ron::Serializer::new(&mut output, Some(PrettyConfig::new()))?;
+
+    let mut level = serializer.serialize_struct("DeLevel", objects.len() + 1)?;
+    level.serialize_field("tiles", tiles)?;
+    for (registration, list) in objects.iter().zip(lists) {
+        registration.save(list, &mut level)?;
+    }
+    level.end()?;
+
+    Ok(output)
+}
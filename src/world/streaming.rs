@@ -0,0 +1,144 @@
+// Levels that are far away from both the player and the camera have their tiles, colliders
+// and objects despawned. The level entity itself stays around with its tile grid, so we
+// still know where it is and how big it is, and can spawn it again when the player comes
+// back.
+
+use crate::{prelude::*, word::SentenceStructure};
+use bevy::ecs::system::EntityCommands;
+use bevy_ecs_tilemap::prelude::*;
+
+use super::{DeLevel, LevelQuery, editor::WorldEditorState, helpers::level_rect};
+use super::objects::{WorldObject, WorldObjects, ObjectId, Placed, WordTag, LevelEntry};
+use super::objects::{Checkpoint, PendingTransition, PlayerPlaced};
+
+#[derive(Resource)]
+pub struct LevelStreaming {
+    pub enabled: bool,
+    /// How close the player or the camera has to get to a level's bounds for it to be
+    /// spawned.
+    pub margin: f32,
+}
+
+impl Default for LevelStreaming {
+    fn default() -> Self {
+        Self { enabled: true, margin: 160. }
+    }
+}
+
+/// Spawned levels are only despawned once they're this much further away than
+/// `LevelStreaming::margin`, so walking back and forth over the edge doesn't respawn them
+/// every frame.
+const UNLOAD_HYSTERESIS: f32 = 64.;
+
+/// Marks a level whose contents are currently despawned.
+#[derive(Component)]
+pub struct Unloaded;
+
+/// The state of a level's objects that has to survive the level being despawned.
+#[derive(Component, Default)]
+pub struct LevelMemory {
+    pub collected_tags: HashSet<ObjectId>,
+    pub sentences: HashMap<ObjectId, SentenceStructure>,
+}
+
+impl LevelMemory {
+    pub fn restore(&self, key: ObjectId, object: &mut EntityCommands) {
+        if self.collected_tags.contains(&key) {
+            object.insert(Visibility::Hidden);
+        }
+        if let Some(sentence) = self.sentences.get(&key) {
+            object.insert(sentence.clone());
+        }
+    }
+}
+
+pub fn spawn_objects<T: WorldObject>(
+    placed: &[Placed<T::InWorld>],
+    assets: &MiscAssets,
+    level: Entity,
+    memory: &LevelMemory,
+    commands: &mut Commands,
+) {
+    for placed in placed {
+        let mut object = commands.spawn((T::bundle(&placed.object, assets), placed.id));
+        memory.restore(placed.id, &mut object);
+        object.set_parent(level);
+    }
+}
+
+fn distance_to_rect(rect: Rect, point: Vec2) -> f32 {
+    (rect.min - point).max(point - rect.max).max(Vec2::ZERO).length()
+}
+
+pub fn stream_levels(
+    streaming: Res<LevelStreaming>,
+    editor_state: Res<State<WorldEditorState>>,
+    player_placed: Res<PlayerPlaced>,
+    checkpoint: Res<Checkpoint>,
+    pending_transition: Res<PendingTransition>,
+    focus: Query<&GlobalTransform, Or<(With<Player>, With<GameCamera>)>>,
+    mut levels: Query<(LevelQuery, &Transform)>,
+    level_assets: Res<Assets<DeLevel>>,
+    assets: Res<MiscAssets>,
+    objects: Res<WorldObjects>,
+    children: Query<&Children>,
+    parents: Query<&Parent>,
+    tags: Query<(&ObjectId, &Visibility), With<WordTag>>,
+    sentences: Query<(&ObjectId, &SentenceStructure)>,
+    mut commands: Commands,
+) {
+    // wait until the player has been placed, so the level they start in isn't streamed out
+    // from under them.
+    if !streaming.enabled || !player_placed.placed { return }
+
+    // the checkpoint's level is kept around, so the player still has their checkpoint to
+    // respawn at after walking away from it.
+    let checkpoint_level = checkpoint.spawner
+        .and_then(|spawner| parents.get(spawner).ok())
+        .map(|level| level.get());
+
+    let focus: Vec<Vec2> = focus.iter().map(|f| f.translation().xy()).collect();
+
+    for (mut level, transform) in &mut levels {
+        if level.loaded_level.tiles.is_empty() { continue }
+        let Some(level_asset) = level_assets.get(level.loaded_level.handle.id())
+            else { continue };
+
+        let rect = level_rect(&level.loaded_level, transform);
+        let distance = focus.iter()
+            .map(|point| distance_to_rect(rect, *point))
+            .fold(f32::INFINITY, f32::min);
+
+        let has_pending_entry = pending_transition.target.as_ref().is_some_and(|target| {
+            level_asset.objects::<LevelEntry>().iter()
+                .any(|entry| target.leads_to(entry.id, &entry.object.name))
+        });
+
+        if level.unloaded && (distance <= streaming.margin || has_pending_entry) {
+            commands.entity(level.entity).remove::<Unloaded>();
+            super::save_and_load::spawn_level_objects(
+                level_asset, &*objects, &*assets, level.entity, &*level.memory, &mut commands);
+
+            // rebuilds the tiles and colliders in refresh_tilemap
+            level.loaded_level.rebuild_all();
+        } else if !level.unloaded &&
+          distance > streaming.margin + UNLOAD_HYSTERESIS &&
+          !has_pending_entry &&
+          Some(level.entity) != checkpoint_level &&
+          // objects placed in the editor only exist as entities until the world is saved
+          *editor_state.get() == WorldEditorState::Off {
+            for descendant in children.iter_descendants(level.entity) {
+                if let Ok((key, visibility)) = tags.get(descendant) &&
+                  *visibility == Visibility::Hidden {
+                    level.memory.collected_tags.insert(*key);
+                }
+                if let Ok((key, sentence)) = sentences.get(descendant) {
+                    level.memory.sentences.insert(*key, sentence.clone());
+                }
+            }
+
+            commands.entity(level.entity).despawn_descendants().insert(Unloaded);
+            *level.storage = TileStorage::empty(*level.size);
+        }
+    }
+}
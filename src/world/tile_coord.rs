@@ -0,0 +1,197 @@
+// Converting between world positions and tiles. Anything that needs to know which tile is
+// where should go through here instead of doing the math itself.
+//
+// The convention:
+// - a tile is addressed as `TileCoord { x, y }`, and stored at `tiles[coord]`, which is
+//   `tiles[(x, y)]`. That makes `x` the grid's *row* index and `y` its *column* index, even
+//   though `x` goes right and `y` goes up in the world. So a level is `rows()` tiles wide and
+//   `cols()` tiles tall.
+// - "level space" is relative to the level's translation. Tile `(x, y)` is centered on
+//   `(x * 16, y * 16)` in level space, so it covers `x * 16 - 8` to `x * 16 + 8`
+//   horizontally.
+// - a `TileRect` goes from `min` up to but not including `max`, in the same `(x, y)`.
+
+use std::ops::{Index, IndexMut};
+
+use bevy::{prelude::*, math::IVec2};
+use bevy_ecs_tilemap::prelude::{TilePos, TilemapSize};
+use grid::Grid;
+
+pub const TILE_SIZE: f32 = 16.;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub struct TileCoord {
+    pub x: usize,
+    pub y: usize,
+}
+
+impl TileCoord {
+    pub const fn new(x: usize, y: usize) -> Self {
+        Self { x, y }
+    }
+
+    /// The tile a position in level space falls into, or `None` if it's left of or below the
+    /// level. It can still be past the other edges, see `in_grid`.
+    pub fn from_level_pos(position: Vec2) -> Option<Self> {
+        let tile = ((position + TILE_SIZE / 2.) / TILE_SIZE).floor();
+        if tile.x < 0. || tile.y < 0. { return None }
+
+        Some(Self::new(tile.x as usize, tile.y as usize))
+    }
+
+    /// The tile a world position falls into, in the level at `level`.
+    pub fn from_world(position: Vec2, level: &Transform) -> Option<Self> {
+        Self::from_level_pos(to_level_space(position, level))
+    }
+
+    /// The center of the tile in level space.
+    pub fn center(self) -> Vec2 {
+        Vec2::new(self.x as f32, self.y as f32) * TILE_SIZE
+    }
+
+    /// The center of the tile in world space, in the level at `level`.
+    pub fn to_world(self, level: &Transform) -> Vec2 {
+        self.center() + level.translation.xy()
+    }
+
+    /// The tile `by` tiles away, or `None` if that's left of or below the level.
+    pub fn offset(self, by: IVec2) -> Option<Self> {
+        Some(Self::new(
+            self.x.checked_add_signed(by.x as isize)?,
+            self.y.checked_add_signed(by.y as isize)?,
+        ))
+    }
+
+    pub fn in_grid<T>(self, grid: &Grid<T>) -> bool {
+        self.x < grid.rows() && self.y < grid.cols()
+    }
+
+    pub fn tile_pos(self) -> TilePos {
+        TilePos { x: self.x as u32, y: self.y as u32 }
+    }
+}
+
+impl<T> Index<TileCoord> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, coord: TileCoord) -> &T {
+        &self[(coord.x, coord.y)]
+    }
+}
+
+impl<T> IndexMut<TileCoord> for Grid<T> {
+    fn index_mut(&mut self, coord: TileCoord) -> &mut T {
+        &mut self[(coord.x, coord.y)]
+    }
+}
+
+/// A rectangle of tiles, from `min` up to but not including `max`.
+#[derive(Component, Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub struct TileRect {
+    pub min: UVec2,
+    pub max: UVec2,
+}
+
+impl TileRect {
+    pub fn new(min: (usize, usize), max: (usize, usize)) -> Self {
+        Self {
+            min: UVec2::new(min.0 as u32, min.1 as u32),
+            max: UVec2::new(max.0 as u32, max.1 as u32),
+        }
+    }
+
+    pub fn single(coord: TileCoord) -> Self {
+        Self::new((coord.x, coord.y), (coord.x + 1, coord.y + 1))
+    }
+
+    /// The smallest rect containing both tiles.
+    pub fn from_corners(a: TileCoord, b: TileCoord) -> Self {
+        Self::single(a).union(Self::single(b))
+    }
+
+    pub fn of_grid<T>(grid: &Grid<T>) -> Self {
+        Self::new((0, 0), (grid.rows(), grid.cols()))
+    }
+
+    pub fn union(self, other: Self) -> Self {
+        Self { min: self.min.min(other.min), max: self.max.max(other.max) }
+    }
+
+    pub fn intersects(self, other: Self) -> bool {
+        self.min.x < other.max.x && other.min.x < self.max.x &&
+            self.min.y < other.max.y && other.min.y < self.max.y
+    }
+
+    pub fn contains(self, coord: TileCoord) -> bool {
+        let (x, y) = (coord.x as u32, coord.y as u32);
+        self.min.x <= x && x < self.max.x && self.min.y <= y && y < self.max.y
+    }
+
+    /// Grows the rect by `by` tiles in every direction, without going outside of `within`.
+    pub fn grow(self, by: u32, within: Self) -> Self {
+        Self {
+            min: self.min.saturating_sub(UVec2::splat(by)).max(within.min),
+            max: (self.max + by).min(within.max),
+        }
+    }
+
+    pub fn width(self) -> usize { (self.max.x - self.min.x) as usize }
+    pub fn height(self) -> usize { (self.max.y - self.min.y) as usize }
+
+    pub fn iter(self) -> impl Iterator<Item = TileCoord> {
+        (self.min.x as usize..self.max.x as usize).flat_map(move |x| {
+            (self.min.y as usize..self.max.y as usize).map(move |y| TileCoord::new(x, y))
+        })
+    }
+
+    /// The area the tiles cover in level space.
+    pub fn level_rect(self) -> Rect {
+        Rect {
+            min: self.min.as_vec2() * TILE_SIZE - TILE_SIZE / 2.,
+            max: self.max.as_vec2() * TILE_SIZE - TILE_SIZE / 2.,
+        }
+    }
+
+    /// The area the tiles cover in world space, in the level at `level`.
+    pub fn world_rect(self, level: &Transform) -> Rect {
+        let rect = self.level_rect();
+        let offset = level.translation.xy();
+        Rect { min: rect.min + offset, max: rect.max + offset }
+    }
+}
+
+pub fn to_level_space(position: Vec2, level: &Transform) -> Vec2 {
+    position - level.translation.xy()
+}
+
+/// The size bevy_ecs_tilemap needs for a level's tiles.
+pub fn tilemap_size<T>(tiles: &Grid<T>) -> TilemapSize {
+    TilemapSize { x: tiles.rows() as u32, y: tiles.cols() as u32 }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tile_centers_match_rects() {
+        for coord in [(0, 0), (3, 0), (0, 5), (7, 2)].map(|(x, y)| TileCoord::new(x, y)) {
+            assert_eq!(TileRect::single(coord).level_rect().center(), coord.center());
+        }
+    }
+
+    #[test]
+    fn world_positions_round_trip() {
+        let level = Transform::from_xyz(160., -48., 0.);
+
+        for coord in [(0, 0), (3, 0), (7, 12)].map(|(x, y)| TileCoord::new(x, y)) {
+            let center = coord.to_world(&level);
+            assert_eq!(TileCoord::from_world(center, &level), Some(coord));
+            // anywhere inside the tile is still the same tile
+            assert_eq!(TileCoord::from_world(center + Vec2::splat(7.9), &level), Some(coord));
+            assert_eq!(TileCoord::from_world(center - Vec2::splat(8.), &level), Some(coord));
+        }
+
+        assert_eq!(TileCoord::from_world(Vec2::new(151., -48.), &level), None);
+    }
+}
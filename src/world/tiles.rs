@@ -0,0 +1,160 @@
+// Tile types, and picking which part of the tileset each tile draws with.
+//
+// Each full tile type, and one-way platforms, have a row in `tileset.bmp`. The 16 columns
+// of a row are the same tile with its edges drawn on different sides: the column is a
+// bitmask of which neighbours the tile connects to (1 = up, 2 = right, 4 = down, 8 = left),
+// so a tile with nothing around it is column 0, and a tile in the middle of a wall is
+// column 15. Slopes don't connect to anything, and share the row after the platforms.
+
+use crate::prelude::*;
+use bevy_ecs_tilemap::prelude::*;
+use bevy_rapier2d::rapier::math::Vector;
+
+use super::{WorldCollider, tile_coord::TileCoord};
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq, Eq, Hash, Default)]
+#[repr(u32)]
+pub enum TileIndex {
+    #[serde(rename = "G")]
+    Ground = 0,
+    #[serde(rename = "D")]
+    Dirt = 1,
+    #[serde(rename = "S")]
+    Stone = 2,
+    #[serde(rename = "W")]
+    Wood = 3,
+    /// Can be jumped up through from below, and stood on from above.
+    #[serde(rename = "P")]
+    Platform = 4,
+    /// 45 degree slope, rising to the right.
+    #[serde(rename = "R")]
+    SlopeRight = 5,
+    /// 45 degree slope, rising to the left.
+    #[serde(rename = "L")]
+    SlopeLeft = 6,
+    /// The shallow slopes rise half a tile per tile (the usual "22.5 degree" pixel art
+    /// slope), so they're made of a low and a high half.
+    #[serde(rename = "Ra")]
+    ShallowRightLow = 7,
+    #[serde(rename = "Rb")]
+    ShallowRightHigh = 8,
+    #[serde(rename = "La")]
+    ShallowLeftLow = 9,
+    #[serde(rename = "Lb")]
+    ShallowLeftHigh = 10,
+    #[default]
+    #[serde(rename = "A")]
+    Air = u32::MAX,
+}
+
+/// The tiles that can be placed in the editor, in the order they show up in the palette.
+pub const TILE_PALETTE: [(TileIndex, &str); 11] = [
+    (TileIndex::Ground, "Ground"),
+    (TileIndex::Dirt, "Dirt"),
+    (TileIndex::Stone, "Stone"),
+    (TileIndex::Wood, "Wood"),
+    (TileIndex::Platform, "One-way Platform"),
+    (TileIndex::SlopeRight, "Slope Right"),
+    (TileIndex::SlopeLeft, "Slope Left"),
+    (TileIndex::ShallowRightLow, "Shallow Slope Right (Low)"),
+    (TileIndex::ShallowRightHigh, "Shallow Slope Right (High)"),
+    (TileIndex::ShallowLeftLow, "Shallow Slope Left (Low)"),
+    (TileIndex::ShallowLeftHigh, "Shallow Slope Left (High)"),
+];
+
+const TILESET_COLUMNS: u32 = 16;
+const PLATFORM_ROW: u32 = 4;
+const SLOPE_ROW: u32 = 5;
+
+impl TileIndex {
+    pub fn is_solid(self) -> bool {
+        self != TileIndex::Air
+    }
+
+    /// Tiles that fill their whole square, and get merged into big rectangular colliders.
+    pub fn is_full(self) -> bool {
+        matches!(self, TileIndex::Ground | TileIndex::Dirt | TileIndex::Stone | TileIndex::Wood)
+    }
+
+    /// The corners of a slope tile's collider, relative to the tile's center.
+    pub fn slope_points(self) -> Option<&'static [Vec2]> {
+        const BL: Vec2 = Vec2::new(-8., -8.);
+        const BR: Vec2 = Vec2::new(8., -8.);
+        const TL: Vec2 = Vec2::new(-8., 8.);
+        const TR: Vec2 = Vec2::new(8., 8.);
+        const ML: Vec2 = Vec2::new(-8., 0.);
+        const MR: Vec2 = Vec2::new(8., 0.);
+
+        match self {
+            TileIndex::SlopeRight => Some(&[BL, BR, TR]),
+            TileIndex::SlopeLeft => Some(&[BL, BR, TL]),
+            TileIndex::ShallowRightLow => Some(&[BL, BR, MR]),
+            TileIndex::ShallowRightHigh => Some(&[BL, BR, TR, ML]),
+            TileIndex::ShallowLeftLow => Some(&[BL, BR, ML]),
+            TileIndex::ShallowLeftHigh => Some(&[BL, BR, MR, TL]),
+            _ => None,
+        }
+    }
+
+    fn texture_index(self, neighbour_mask: u32) -> u32 {
+        match self {
+            TileIndex::Platform => PLATFORM_ROW * TILESET_COLUMNS + neighbour_mask,
+            slope if slope.slope_points().is_some() =>
+                SLOPE_ROW * TILESET_COLUMNS + (slope as u32 - TileIndex::SlopeRight as u32),
+            full => full as u32 * TILESET_COLUMNS + neighbour_mask,
+        }
+    }
+
+    /// Whether this tile hides its edge on the side facing `other`.
+    pub fn connects_to(self, other: TileIndex) -> bool {
+        self == other
+    }
+}
+
+/// Picks the tileset index for the tile at `(x, y)`, based on which of its neighbours it
+/// connects to. Tiles at the edge of the level connect to the outside, so levels don't have
+/// a border drawn around them.
+pub fn autotile(tiles: &Grid<TileIndex>, coord: TileCoord) -> Option<TileTextureIndex> {
+    let tile = tiles[coord];
+    if !tile.is_solid() { return None }
+
+    let connects = |dx: i32, dy: i32| {
+        match coord.offset(IVec2::new(dx, dy)) {
+            Some(neighbour) if neighbour.in_grid(tiles) => tile.connects_to(tiles[neighbour]),
+            _ => true,
+        }
+    };
+
+    let mask = connects(0, 1) as u32
+        | (connects(1, 0) as u32) << 1
+        | (connects(0, -1) as u32) << 2
+        | (connects(-1, 0) as u32) << 3;
+
+    Some(TileTextureIndex(tile.texture_index(mask)))
+}
+
+/// Physics hooks that let things through one-way platforms from below.
+#[derive(SystemParam)]
+pub struct OneWayPlatformHooks<'w, 's> {
+    world_colliders: Query<'w, 's, &'static WorldCollider>,
+}
+
+impl BevyPhysicsHooks for OneWayPlatformHooks<'_, '_> {
+    fn modify_solver_contacts(&self, mut context: ContactModificationContextView) {
+        let is_platform = |entity| {
+            self.world_colliders.get(entity).is_ok_and(|c| *c == WorldCollider::OneWay)
+        };
+
+        // the contact normal is from the first collider's point of view, so only contacts
+        // that push the other collider up off the top of the platform are kept.
+        let allowed_normal = if is_platform(context.collider1()) {
+            Vector::y()
+        } else if is_platform(context.collider2()) {
+            -Vector::y()
+        } else {
+            return;
+        };
+
+        context.raw.update_as_oneway_platform(&allowed_normal, 0.1);
+    }
+}